@@ -0,0 +1,91 @@
+//! Minimal calendar-date helpers. Dates are represented as days since the Unix
+//! epoch, which is all the precision rules need for deadlines.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Days since 1970-01-01 for the proleptic Gregorian date `y-m-d`.
+/// See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn is_leap_year(y: i64) -> bool {
+    (y % 4 == 0 && y % 100 != 0) || y % 400 == 0
+}
+
+/// Parse a `YYYY-MM-DD` date into days since the Unix epoch. Returns `None` for
+/// anything that is not a valid calendar date.
+pub fn parse_ymd(s: &str) -> Option<i64> {
+    let mut parts = s.splitn(3, '-');
+    let (y, m, d) = (parts.next()?, parts.next()?, parts.next()?);
+    if y.len() != 4 || m.len() != 2 || d.len() != 2 {
+        return None;
+    }
+    let (y, m, d): (i64, i64, i64) = (y.parse().ok()?, m.parse().ok()?, d.parse().ok()?);
+
+    let days_in_month = match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(y) => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if d < 1 || d > days_in_month {
+        return None;
+    }
+
+    Some(days_from_civil(y, m, d))
+}
+
+/// The proleptic Gregorian year containing `days` since the Unix epoch.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub fn year(days: i64) -> i64 {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let y = yoe + era * 400;
+    if mp >= 10 {
+        y + 1
+    } else {
+        y
+    }
+}
+
+/// The current UTC date as days since the Unix epoch.
+pub fn today() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    (secs / 86_400) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_calendar_dates() {
+        assert_eq!(parse_ymd("1970-01-01"), Some(0));
+        assert_eq!(parse_ymd("2000-03-01"), Some(11_017));
+        assert_eq!(parse_ymd("2024-02-29"), Some(19_782));
+        assert_eq!(parse_ymd("2023-02-29"), None);
+        assert_eq!(parse_ymd("2026-13-01"), None);
+        assert_eq!(parse_ymd("26-12-31"), None);
+    }
+
+    #[test]
+    fn years_of_dates() {
+        assert_eq!(year(0), 1970);
+        assert_eq!(year(parse_ymd("2024-02-29").unwrap()), 2024);
+        assert_eq!(year(parse_ymd("2025-12-31").unwrap()), 2025);
+        assert_eq!(year(parse_ymd("2026-01-01").unwrap()), 2026);
+    }
+}
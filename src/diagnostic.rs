@@ -1,6 +1,11 @@
+use crate::encoding::{self, Contents};
+use crate::run;
 use serde::Serialize;
 use serde_sarif::sarif;
+use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Clone, Serialize)]
 pub enum Severity {
@@ -21,10 +26,80 @@ impl fmt::Display for Severity {
     }
 }
 
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            "note" => Ok(Severity::Note),
+            "none" => Ok(Severity::None),
+            _ => Err(anyhow::anyhow!(
+                "unknown severity {:?}; expected one of error, warning, note, none",
+                s
+            )),
+        }
+    }
+}
+
+/// What `Position::character` counts from the start of its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes, as returned by `str` and regex offsets
+    Utf8,
+    /// UTF-16 code units, as used by VS Code, LSP and GitHub
+    Utf16,
+    /// Unicode scalar values, as counted by `str::chars`
+    CodePoint,
+}
+
+impl ColumnUnit {
+    /// The SARIF `columnKind` for columns in this unit. SARIF can't express
+    /// byte columns, so those have to be converted first.
+    pub fn sarif_column_kind(&self) -> Option<sarif::ResultColumnKind> {
+        match self {
+            ColumnUnit::Utf8 => None,
+            ColumnUnit::Utf16 => Some(sarif::ResultColumnKind::Utf16CodeUnits),
+            ColumnUnit::CodePoint => Some(sarif::ResultColumnKind::UnicodeCodePoints),
+        }
+    }
+
+    fn width(&self, c: char) -> u64 {
+        match self {
+            ColumnUnit::Utf8 => c.len_utf8() as u64,
+            ColumnUnit::Utf16 => c.len_utf16() as u64,
+            ColumnUnit::CodePoint => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Position {
     pub line: u64,
     pub character: u64,
+    pub unit: ColumnUnit,
+}
+
+impl Position {
+    /// Convert `character` to `unit`, given the text of the line it points into.
+    /// Columns past the end of the line keep their distance from the end.
+    pub fn convert(&self, line_text: &str, unit: ColumnUnit) -> Position {
+        let mut from = 0;
+        let mut to = 0;
+        for c in line_text.chars() {
+            if from >= self.character {
+                break;
+            }
+            from += self.unit.width(c);
+            to += unit.width(c);
+        }
+        Position {
+            line: self.line,
+            character: to + self.character.saturating_sub(from),
+            unit,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -35,6 +110,9 @@ pub struct Range {
 
 #[derive(Clone, Serialize)]
 pub struct Replacement {
+    /// File the replacement applies to, when it is not the diagnostic's own file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
     pub deleted_region: Range,
     pub inserted_content: String,
 }
@@ -48,6 +126,67 @@ pub struct Diagnostic {
     pub replacements: Option<Vec<Replacement>>,
 }
 
+impl Range {
+    fn convert(&mut self, lines: &[String], unit: ColumnUnit) {
+        for position in [&mut self.start, &mut self.end] {
+            if position.unit != unit {
+                let line = lines.get(position.line as usize).map_or("", String::as_str);
+                *position = position.convert(line, unit);
+            }
+        }
+    }
+}
+
+/// Converts diagnostic columns between units, reading each file at most once.
+pub struct ColumnConverter {
+    /// Absolute directory that relative paths are resolved against
+    root: PathBuf,
+    files: HashMap<PathBuf, Vec<String>>,
+}
+
+impl ColumnConverter {
+    /// A converter for paths relative to `workspace`, or to the current
+    /// directory when there is none.
+    pub fn new(workspace: Option<&Path>) -> ColumnConverter {
+        ColumnConverter {
+            root: run::workspace_root(workspace),
+            files: HashMap::new(),
+        }
+    }
+
+    fn lines(&mut self, path: &str) -> &[String] {
+        let key = run::relative_path(&self.root, Path::new(path));
+        let resolved = self.root.join(&key);
+        self.files
+            .entry(key)
+            .or_insert_with(|| match encoding::read(&resolved) {
+                Ok(Contents::Text { text, .. }) => text.lines().map(str::to_string).collect(),
+                _ => Vec::new(),
+            })
+    }
+
+    /// Rewrite every position in `diagnostics`, including fixes, in `unit`.
+    pub fn convert(&mut self, diagnostics: &mut [Diagnostic], unit: ColumnUnit) {
+        for d in diagnostics {
+            let needs_conversion = d
+                .range
+                .iter()
+                .chain(d.replacements.iter().flatten().map(|r| &r.deleted_region))
+                .any(|r| r.start.unit != unit || r.end.unit != unit);
+            if !needs_conversion {
+                continue;
+            }
+            if let Some(range) = &mut d.range {
+                range.convert(self.lines(&d.path), unit);
+            }
+            for replacement in d.replacements.iter_mut().flatten() {
+                let lines = self.lines(replacement.path.as_deref().unwrap_or(&d.path));
+                replacement.deleted_region.convert(lines, unit);
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Default)]
 pub struct Diagnostics {
     pub diagnostics: Vec<Diagnostic>,
@@ -82,11 +221,10 @@ impl Diagnostic {
             );
         }
 
-        let fixes = if let Some(replacements) = &self.replacements {
-            Some(vec![self.build_fix(replacements)])
-        } else {
-            None
-        };
+        let fixes = self
+            .replacements
+            .as_ref()
+            .map(|replacements| vec![self.build_fix(replacements)]);
 
         sarif::ResultBuilder::default()
             .level(self.severity.to_string())
@@ -106,48 +244,63 @@ impl Diagnostic {
             .unwrap()
     }
 
+    /// One artifact change per file touched by `replacements`, in order of first appearance.
     pub fn build_fix(&self, replacements: &[Replacement]) -> sarif::Fix {
+        let mut by_path: Vec<(&str, Vec<&Replacement>)> = Vec::new();
+        for replacement in replacements {
+            let path = replacement.path.as_deref().unwrap_or(&self.path);
+            match by_path.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, group)) => group.push(replacement),
+                None => by_path.push((path, vec![replacement])),
+            }
+        }
+
         sarif::FixBuilder::default()
-            .artifact_changes([sarif::ArtifactChangeBuilder::default()
-                .artifact_location(
-                    sarif::ArtifactLocationBuilder::default()
-                        .uri(self.path.clone())
-                        .build()
-                        .unwrap(),
-                )
-                .replacements(
-                    replacements
-                        .iter()
-                        .map(|replacement| {
-                            sarif::ReplacementBuilder::default()
-                                .deleted_region(
-                                    sarif::RegionBuilder::default()
-                                        .start_line(
-                                            replacement.deleted_region.start.line as i64 + 1,
-                                        )
-                                        .start_column(
-                                            replacement.deleted_region.start.character as i64 + 1,
-                                        )
-                                        .end_line(replacement.deleted_region.end.line as i64 + 1)
-                                        .end_column(
-                                            replacement.deleted_region.end.character as i64 + 1,
-                                        )
-                                        .build()
-                                        .unwrap(),
-                                )
-                                .inserted_content(
-                                    sarif::ArtifactContentBuilder::default()
-                                        .text(replacement.inserted_content.clone())
-                                        .build()
-                                        .unwrap(),
-                                )
-                                .build()
-                                .unwrap()
-                        })
-                        .collect::<Vec<_>>(),
-                )
-                .build()
-                .unwrap()])
+            .artifact_changes(
+                by_path
+                    .into_iter()
+                    .map(|(path, group)| Self::build_artifact_change(path, &group))
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn build_artifact_change(path: &str, replacements: &[&Replacement]) -> sarif::ArtifactChange {
+        sarif::ArtifactChangeBuilder::default()
+            .artifact_location(
+                sarif::ArtifactLocationBuilder::default()
+                    .uri(path.to_string())
+                    .build()
+                    .unwrap(),
+            )
+            .replacements(
+                replacements
+                    .iter()
+                    .map(|replacement| {
+                        sarif::ReplacementBuilder::default()
+                            .deleted_region(
+                                sarif::RegionBuilder::default()
+                                    .start_line(replacement.deleted_region.start.line as i64 + 1)
+                                    .start_column(
+                                        replacement.deleted_region.start.character as i64 + 1,
+                                    )
+                                    .end_line(replacement.deleted_region.end.line as i64 + 1)
+                                    .end_column(replacement.deleted_region.end.character as i64 + 1)
+                                    .build()
+                                    .unwrap(),
+                            )
+                            .inserted_content(
+                                sarif::ArtifactContentBuilder::default()
+                                    .text(replacement.inserted_content.clone())
+                                    .build()
+                                    .unwrap(),
+                            )
+                            .build()
+                            .unwrap()
+                    })
+                    .collect::<Vec<_>>(),
+            )
             .build()
             .unwrap()
     }
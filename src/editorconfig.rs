@@ -0,0 +1,126 @@
+//! Just enough of EditorConfig (https://editorconfig.org) to find the
+//! indentation style of a file: `indent_style`, `indent_size` and `tab_width`.
+use glob_match::glob_match;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Indentation {
+    pub style: Option<IndentStyle>,
+    pub size: Option<usize>,
+}
+
+/// Whether an `.editorconfig` file stops the search for parent files.
+fn is_root(content: &str) -> bool {
+    content
+        .lines()
+        .take_while(|l| !l.trim().starts_with('['))
+        .any(|l| {
+            l.split_once('=').is_some_and(|(k, v)| {
+                k.trim().eq_ignore_ascii_case("root") && v.trim().eq_ignore_ascii_case("true")
+            })
+        })
+}
+
+/// Apply the properties of one `.editorconfig` file that match `rel_path`,
+/// relative to the directory holding it.
+fn apply(content: &str, rel_path: &str, indentation: &mut Indentation) {
+    let mut in_matching_section = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            // Patterns without a slash match the file name in any directory
+            let pattern = match section.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if section.contains('/') => section.to_string(),
+                None => format!("**/{}", section),
+            };
+            in_matching_section = glob_match(&pattern, rel_path);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_ascii_lowercase();
+        if !in_matching_section {
+            continue;
+        }
+        match key.as_str() {
+            "indent_style" => {
+                indentation.style = match value.as_str() {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                }
+            }
+            "indent_size" | "tab_width" => {
+                if let Ok(size) = value.parse() {
+                    indentation.size = Some(size);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Indentation settings for `path`, from the `.editorconfig` files in its
+/// directory and each parent up to the first one marked `root = true`.
+pub fn indentation(path: &Path) -> Indentation {
+    let Ok(path) = std::path::absolute(path) else {
+        return Indentation::default();
+    };
+
+    // Closer files take precedence, so collect them first and apply outermost first
+    let mut configs = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        let Ok(content) = std::fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let Ok(rel_path) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+        let root = is_root(&content);
+        configs.push((content, rel_path));
+        if root {
+            break;
+        }
+    }
+
+    let mut indentation = Indentation::default();
+    for (content, rel_path) in configs.iter().rev() {
+        apply(content, rel_path, &mut indentation);
+    }
+    indentation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_sections_override_earlier_ones() {
+        let content = "root = true\n\n[*]\nindent_style = space\nindent_size = 2\n\n[*.go]\nindent_style = tab\n\n[/Makefile]\nindent_style = tab\n";
+        assert!(is_root(content));
+        let mut go = Indentation::default();
+        apply(content, "cmd/main.go", &mut go);
+        assert_eq!(go.style, Some(IndentStyle::Tab));
+        assert_eq!(go.size, Some(2));
+
+        let mut makefile = Indentation::default();
+        apply(content, "sub/Makefile", &mut makefile);
+        assert_eq!(makefile.style, Some(IndentStyle::Space));
+    }
+}
@@ -0,0 +1,178 @@
+//! Sniffs the text encoding of a file so rules can scan UTF-16 and legacy
+//! Latin-1 sources as well as UTF-8, and tell undecodable files apart from
+//! binary ones.
+use crate::diagnostic::{Diagnostic, Severity};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    /// Whether offsets into the decoded text line up with the bytes on disk,
+    /// so fixes computed on the decoded text can be applied to the file.
+    pub fn is_utf8(&self) -> bool {
+        *self == Encoding::Utf8
+    }
+}
+
+#[derive(Debug)]
+pub enum Contents {
+    Text {
+        text: String,
+        encoding: Encoding,
+    },
+    Binary,
+    /// Looks like text but can't be decoded; holds the reason
+    Undecodable(String),
+}
+
+const SNIFF_LEN: usize = 4096;
+
+pub fn decode(bytes: &[u8]) -> Contents {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return match std::str::from_utf8(rest) {
+            Ok(text) => Contents::Text {
+                text: text.to_string(),
+                encoding: Encoding::Utf8Bom,
+            },
+            Err(e) => Contents::Undecodable(format!(
+                "has a UTF-8 byte order mark but invalid UTF-8 at byte {}",
+                e.valid_up_to() + 3
+            )),
+        };
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(rest, Encoding::Utf16Le);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        return decode_utf16(rest, Encoding::Utf16Be);
+    }
+
+    let sniff = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sniff.contains(&0) {
+        // Mostly-ASCII UTF-16 without a byte order mark has a NUL in every other byte
+        return match utf16_without_bom(sniff) {
+            Some(encoding) => decode_utf16(bytes, encoding),
+            None => Contents::Binary,
+        };
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Contents::Text {
+            text: text.to_string(),
+            encoding: Encoding::Utf8,
+        },
+        // C1 control characters essentially never appear in Latin-1 text, so
+        // their presence means some other legacy encoding
+        Err(e) => match bytes.iter().position(|b| (0x80..0xA0).contains(b)) {
+            Some(i) => Contents::Undecodable(format!(
+                "is neither UTF-8 (invalid at byte {}) nor Latin-1 (C1 control byte 0x{:02X} at byte {})",
+                e.valid_up_to(),
+                bytes[i],
+                i
+            )),
+            None => Contents::Text {
+                text: bytes.iter().map(|&b| b as char).collect(),
+                encoding: Encoding::Latin1,
+            },
+        },
+    }
+}
+
+fn utf16_without_bom(sniff: &[u8]) -> Option<Encoding> {
+    if sniff.len() < 2 || sniff.len() % 2 != 0 {
+        return None;
+    }
+    let (mut even, mut odd) = (0, 0);
+    for pair in sniff.chunks_exact(2) {
+        even += (pair[0] == 0) as usize;
+        odd += (pair[1] == 0) as usize;
+    }
+    let units = sniff.len() / 2;
+    if odd * 10 >= units * 9 && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= units * 9 && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], encoding: Encoding) -> Contents {
+    if bytes.len() % 2 != 0 {
+        return Contents::Undecodable("is UTF-16 with an odd number of bytes".to_string());
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| match encoding {
+            Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect();
+    match String::from_utf16(&units) {
+        Ok(text) => Contents::Text { text, encoding },
+        Err(_) => Contents::Undecodable("is UTF-16 with unpaired surrogates".to_string()),
+    }
+}
+
+/// Read and decode `path`.
+pub fn read(path: &Path) -> std::io::Result<Contents> {
+    Ok(decode(&std::fs::read(path)?))
+}
+
+/// Note reported when `rule` skips an undecodable file, instead of failing the rule.
+pub fn skipped(path: &Path, rule: &str, reason: &str) -> Diagnostic {
+    Diagnostic {
+        path: path.to_string_lossy().to_string(),
+        range: None,
+        severity: Severity::Note,
+        code: "toolbox-undecodable-file".to_string(),
+        message: format!("{} skipped this file: it {}", rule, reason),
+        replacements: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8]) -> (String, Encoding) {
+        match decode(bytes) {
+            Contents::Text { text, encoding } => (text, encoding),
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn sniffs_encodings() {
+        assert_eq!(text(b"abc"), ("abc".to_string(), Encoding::Utf8));
+        assert_eq!(
+            text(b"\xEF\xBB\xBFabc"),
+            ("abc".to_string(), Encoding::Utf8Bom)
+        );
+        assert_eq!(
+            text(b"\xFF\xFEa\0b\0"),
+            ("ab".to_string(), Encoding::Utf16Le)
+        );
+        assert_eq!(
+            text(b"\xFE\xFF\0a\0b"),
+            ("ab".to_string(), Encoding::Utf16Be)
+        );
+        assert_eq!(text(b"a\0b\0c\0"), ("abc".to_string(), Encoding::Utf16Le));
+        assert_eq!(
+            text(b"caf\xE9"),
+            ("caf\u{e9}".to_string(), Encoding::Latin1)
+        );
+        assert!(matches!(decode(b"\x89PNG\0\0\x12"), Contents::Binary));
+        assert!(matches!(
+            decode(b"\x93quoted\x94"),
+            Contents::Undecodable(_)
+        ));
+    }
+}
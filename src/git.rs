@@ -1,4 +1,4 @@
-use git2::{AttrCheckFlags, AttrValue, Delta, DiffOptions, Repository};
+use git2::{AttrCheckFlags, AttrValue, Delta, DiffOptions, Oid, Patch, Repository};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, RwLock};
@@ -56,6 +56,13 @@ fn is_lfs(repo: &Repository, path: &Path) -> bool {
     result
 }
 
+/// Whether `path`, relative to the root of the repository containing
+/// `repo_path`, is stored in Git LFS according to its gitattributes.
+pub fn is_lfs_tracked(path: &Path, repo_path: Option<&Path>) -> anyhow::Result<bool> {
+    let repo = Repository::discover(repo_path.unwrap_or(Path::new(".")))?;
+    Ok(is_lfs(&repo, path))
+}
+
 pub fn modified_since(upstream: &str, repo_path: Option<&Path>) -> anyhow::Result<FileChanges> {
     let path = repo_path.unwrap_or(Path::new("."));
     let repo = Repository::open(path)?;
@@ -68,6 +75,7 @@ pub fn modified_since(upstream: &str, repo_path: Option<&Path>) -> anyhow::Resul
     let diff = {
         let mut diff_opts = DiffOptions::new();
         diff_opts.include_untracked(true);
+        diff_opts.recurse_untracked_dirs(true);
 
         repo.diff_tree_to_workdir_with_index(Some(&upstream_tree), Some(&mut diff_opts))?
     };
@@ -101,7 +109,8 @@ pub fn modified_since(upstream: &str, repo_path: Option<&Path>) -> anyhow::Resul
             if let Some(path) = delta.new_file().path() {
                 if !is_lfs(&repo, path) {
                     match delta.status() {
-                        Delta::Added => {
+                        // Untracked files are new to the repository just like staged ones
+                        Delta::Added | Delta::Untracked => {
                             ret.paths.insert(path.to_path_buf(), FileStatus::Added);
                         }
                         Delta::Modified => {
@@ -215,3 +224,109 @@ pub fn get_upstream_content(
     let content = std::str::from_utf8(blob.content())?;
     Ok(content.to_string())
 }
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub sha: String,
+    pub summary: String,
+    pub message: String,
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> anyhow::Result<Oid> {
+    let commit = match repo.find_reference(rev) {
+        Ok(reference) => reference.peel_to_commit()?,
+        _ => repo.revparse_single(rev)?.peel_to_commit()?,
+    };
+    Ok(commit.id())
+}
+
+/// Commits reachable from HEAD but not from the merge-base of HEAD and
+/// `upstream`, newest first.
+pub fn commits_since(upstream: &str, repo_path: Option<&Path>) -> anyhow::Result<Vec<Commit>> {
+    let path = repo_path.unwrap_or(Path::new("."));
+    let repo = Repository::open(path)?;
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    let base = repo.merge_base(head, resolve_commit(&repo, upstream)?)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push(head)?;
+    walk.hide(base)?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        commits.push(Commit {
+            sha: commit.id().to_string(),
+            summary: String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default())
+                .to_string(),
+            message: String::from_utf8_lossy(commit.message_bytes()).to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Short name of the checked out branch, or None when HEAD is detached.
+pub fn current_branch(repo_path: Option<&Path>) -> anyhow::Result<Option<String>> {
+    let path = repo_path.unwrap_or(Path::new("."));
+    let repo = Repository::open(path)?;
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    Ok(head.shorthand().map(str::to_string))
+}
+
+/// Commit time (seconds since the Unix epoch) of the last change to each line
+/// of `file_path`, blamed against its working tree content. Lines that are not
+/// committed yet are `None`.
+pub fn blame_times(file_path: &str, repo_path: Option<&Path>) -> anyhow::Result<Vec<Option<i64>>> {
+    let path = repo_path.unwrap_or(Path::new("."));
+    let repo = Repository::discover(path)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("repository has no working tree"))?;
+
+    let content = std::fs::read(workdir.join(file_path))?;
+    let line_count = content.split(|&b| b == b'\n').count();
+
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let Ok(entry) = head_tree.get_path(Path::new(file_path)) else {
+        // Not committed yet
+        return Ok(vec![None; line_count]);
+    };
+    let head_blob = repo.find_blob(entry.id())?;
+
+    // Map working tree lines to the committed lines they are unchanged from.
+    // Blame::blame_buffer would do this for us, but libgit2 crashes in it for
+    // some edits, so diff the buffers with the whole file as context instead.
+    let head_lines: Vec<Option<usize>> = if head_blob.content() == content.as_slice() {
+        (1..=line_count).map(Some).collect()
+    } else {
+        let mut mapping = vec![None; line_count];
+        let mut opts = DiffOptions::new();
+        opts.context_lines(u32::MAX);
+        let patch = Patch::from_blob_and_buffer(&head_blob, None, &content, None, Some(&mut opts))?;
+        for hunk in 0..patch.num_hunks() {
+            for i in 0..patch.num_lines_in_hunk(hunk)? {
+                let line = patch.line_in_hunk(hunk, i)?;
+                if let (Some(old), Some(new)) = (line.old_lineno(), line.new_lineno()) {
+                    if let Some(slot) = mapping.get_mut(new as usize - 1) {
+                        *slot = Some(old as usize);
+                    }
+                }
+            }
+        }
+        mapping
+    };
+
+    let blame = repo.blame_file(Path::new(file_path), None)?;
+    Ok(head_lines
+        .into_iter()
+        .map(|line| {
+            blame
+                .get_line(line?)
+                .map(|hunk| hunk.final_signature().when().seconds())
+        })
+        .collect())
+}
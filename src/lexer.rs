@@ -0,0 +1,550 @@
+//! A deliberately small lexer that splits lines of source into code, comment
+//! and string spans, keyed by file extension. It does not aim to be exact for
+//! every language, only good enough to tell rules where a match appeared.
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKind {
+    Code,
+    Comment,
+    String,
+}
+
+/// A classified byte range within a single line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub kind: SpanKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Language {
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    /// String delimiters that close on the same line
+    strings: &'static [&'static str],
+    /// String delimiters that may span lines, e.g. Python's `"""`
+    multiline_strings: &'static [&'static str],
+    /// Whether `'x'` is a character literal, in a language where `'` is
+    /// otherwise not a string delimiter
+    char_literals: bool,
+    /// Whether Rust's `r"..."` and `r#"..."#` raw strings are recognized
+    raw_strings: bool,
+}
+
+impl Language {
+    /// The usual line comment marker, e.g. `//`
+    pub fn line_comment(&self) -> Option<&'static str> {
+        self.line_comments.first().copied()
+    }
+
+    /// The usual block comment delimiters, e.g. `("/*", "*/")`
+    pub fn block_comment(&self) -> Option<(&'static str, &'static str)> {
+        self.block_comments.first().copied()
+    }
+}
+
+const C_LIKE: Language = Language {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+// `'` also introduces lifetimes, so it is only a character literal when closed
+const RUST: Language = Language {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    strings: &["\""],
+    multiline_strings: &[],
+    char_literals: true,
+    raw_strings: true,
+};
+
+const JS_LIKE: Language = Language {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    strings: &["\"", "'"],
+    multiline_strings: &["`"],
+    char_literals: false,
+    raw_strings: false,
+};
+
+const PYTHON: Language = Language {
+    line_comments: &["#"],
+    block_comments: &[],
+    strings: &["\"", "'"],
+    multiline_strings: &["\"\"\"", "'''"],
+    char_literals: false,
+    raw_strings: false,
+};
+
+const HASH: Language = Language {
+    line_comments: &["#"],
+    block_comments: &[],
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+const SQL: Language = Language {
+    line_comments: &["--"],
+    block_comments: &[("/*", "*/")],
+    strings: &["'", "\""],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+const LUA: Language = Language {
+    line_comments: &["--"],
+    block_comments: &[("--[[", "]]")],
+    strings: &["'", "\""],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+// `'` also appears in names such as `x'`
+const HASKELL: Language = Language {
+    line_comments: &["--"],
+    block_comments: &[("{-", "-}")],
+    strings: &["\""],
+    multiline_strings: &[],
+    char_literals: true,
+    raw_strings: false,
+};
+
+pub const MARKUP: Language = Language {
+    line_comments: &[],
+    block_comments: &[("<!--", "-->")],
+    strings: &[],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+const CSS: Language = Language {
+    line_comments: &[],
+    block_comments: &[("/*", "*/")],
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    char_literals: false,
+    raw_strings: false,
+};
+
+/// The language used to lex `path`, or None when the extension is not known.
+pub fn language_for(path: &Path) -> Option<&'static Language> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let lang = match ext.as_str() {
+        "rs" => &RUST,
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "java" | "kt" | "kts" | "scala"
+        | "cs" | "swift" | "dart" | "proto" | "m" | "mm" => &C_LIKE,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "go" => &JS_LIKE,
+        "py" | "pyi" => &PYTHON,
+        "sh" | "bash" | "zsh" | "rb" | "pl" | "yaml" | "yml" | "toml" | "r" | "cmake" | "bzl"
+        | "star" | "tf" => &HASH,
+        "sql" => &SQL,
+        "lua" => &LUA,
+        "hs" | "lhs" => &HASKELL,
+        "html" | "htm" | "xml" | "svg" | "vue" => &MARKUP,
+        "css" | "scss" | "less" => &CSS,
+        _ => return None,
+    };
+    Some(lang)
+}
+
+enum State {
+    Code,
+    BlockComment(&'static str),
+    MultilineString(&'static str),
+    /// A raw string closed by `"` and this many `#`
+    RawString(usize),
+}
+
+/// Lexes a file one line at a time, carrying block comments and multi-line
+/// strings across lines.
+pub struct Lexer {
+    lang: &'static Language,
+    state: State,
+}
+
+impl Lexer {
+    pub fn new(lang: &'static Language) -> Lexer {
+        Lexer {
+            lang,
+            state: State::Code,
+        }
+    }
+
+    pub fn for_path(path: &Path) -> Option<Lexer> {
+        language_for(path).map(Lexer::new)
+    }
+
+    /// Classify `line`, which must be the next line of the file.
+    pub fn spans(&mut self, line: &str) -> Vec<Span> {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut push = |kind: SpanKind, start: usize, end: usize| {
+            if start >= end {
+                return;
+            }
+            match spans.last_mut() {
+                Some(last) if last.kind == kind && last.end == start => last.end = end,
+                _ => spans.push(Span { kind, start, end }),
+            }
+        };
+
+        let mut pos = 0;
+        while pos < line.len() {
+            match self.state {
+                State::BlockComment(close) => match line[pos..].find(close) {
+                    Some(i) => {
+                        push(SpanKind::Comment, pos, pos + i + close.len());
+                        pos += i + close.len();
+                        self.state = State::Code;
+                    }
+                    None => {
+                        push(SpanKind::Comment, pos, line.len());
+                        pos = line.len();
+                    }
+                },
+                State::MultilineString(close) => match find_unescaped(&line[pos..], close) {
+                    Some(i) => {
+                        push(SpanKind::String, pos, pos + i + close.len());
+                        pos += i + close.len();
+                        self.state = State::Code;
+                    }
+                    None => {
+                        push(SpanKind::String, pos, line.len());
+                        pos = line.len();
+                    }
+                },
+                State::RawString(hashes) => {
+                    let close = format!("\"{}", "#".repeat(hashes));
+                    match line[pos..].find(&close) {
+                        Some(i) => {
+                            push(SpanKind::String, pos, pos + i + close.len());
+                            pos += i + close.len();
+                            self.state = State::Code;
+                        }
+                        None => {
+                            push(SpanKind::String, pos, line.len());
+                            pos = line.len();
+                        }
+                    }
+                }
+                State::Code => {
+                    let rest = &line[pos..];
+                    let after_ident = line[..pos]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+                    // Block comments first: Lua's `--[[` also starts a line comment
+                    if let Some((open, close)) = self
+                        .lang
+                        .block_comments
+                        .iter()
+                        .find(|(open, _)| rest.starts_with(open))
+                    {
+                        push(SpanKind::Comment, pos, pos + open.len());
+                        pos += open.len();
+                        self.state = State::BlockComment(close);
+                    } else if self.lang.line_comments.iter().any(|c| rest.starts_with(c)) {
+                        push(SpanKind::Comment, pos, line.len());
+                        pos = line.len();
+                    } else if let Some((open, hashes)) =
+                        raw_string_open(rest).filter(|_| self.lang.raw_strings && !after_ident)
+                    {
+                        push(SpanKind::String, pos, pos + open);
+                        pos += open;
+                        self.state = State::RawString(hashes);
+                    } else if let Some(len) =
+                        char_literal(rest).filter(|_| self.lang.char_literals && !after_ident)
+                    {
+                        push(SpanKind::String, pos, pos + len);
+                        pos += len;
+                    } else if let Some(delim) = self
+                        .lang
+                        .multiline_strings
+                        .iter()
+                        .find(|d| rest.starts_with(*d))
+                    {
+                        push(SpanKind::String, pos, pos + delim.len());
+                        pos += delim.len();
+                        self.state = State::MultilineString(delim);
+                    } else if let Some(delim) =
+                        self.lang.strings.iter().find(|d| rest.starts_with(*d))
+                    {
+                        let body = pos + delim.len();
+                        // Unterminated strings end with the line
+                        let end = find_unescaped(&line[body..], delim)
+                            .map_or(line.len(), |i| body + i + delim.len());
+                        push(SpanKind::String, pos, end);
+                        pos = end;
+                    } else {
+                        let len = rest.chars().next().map_or(1, char::len_utf8);
+                        push(SpanKind::Code, pos, pos + len);
+                        pos += len;
+                    }
+                }
+            }
+        }
+
+        spans
+    }
+}
+
+/// Length of the opening of a raw string at the start of `s`, e.g. `br##"`,
+/// and the number of `#` in it.
+fn raw_string_open(s: &str) -> Option<(usize, usize)> {
+    let rest = s.strip_prefix('b').unwrap_or(s).strip_prefix('r')?;
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    rest[hashes..]
+        .starts_with('"')
+        .then(|| (s.len() - rest.len() + hashes + 1, hashes))
+}
+
+/// Length of a character literal such as `'a'` or `'\n'` at the start of `s`.
+/// Lifetimes and other unclosed quotes are not literals.
+fn char_literal(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    chars.next().filter(|&(_, c)| c == '\'')?;
+    let (_, c) = chars.next()?;
+    if c == '\\' {
+        // Skip the escaped character, then allow for `\u{10FFFF}`
+        let (i, escaped) = chars.next()?;
+        let body = i + escaped.len_utf8();
+        return s[body..]
+            .char_indices()
+            .take(9)
+            .find(|&(_, c)| c == '\'')
+            .map(|(j, _)| body + j + 1);
+    }
+    let (i, close) = chars.next()?;
+    (close == '\'').then_some(i + 1)
+}
+
+/// Offset of the first `delim` in `s` not preceded by a backslash escape.
+fn find_unescaped(s: &str, delim: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if s[i..].starts_with(delim) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// The kind of span containing byte `offset`; offsets past the last span are code.
+pub fn kind_at(spans: &[Span], offset: usize) -> SpanKind {
+    spans
+        .iter()
+        .find(|s| s.start <= offset && offset < s.end)
+        .map_or(SpanKind::Code, |s| s.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(lexer: &mut Lexer, line: &str) -> Vec<(SpanKind, String)> {
+        lexer
+            .spans(line)
+            .into_iter()
+            .map(|s| (s.kind, line[s.start..s.end].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_c_like_lines() {
+        let mut lexer = Lexer::new(&JS_LIKE);
+        assert_eq!(
+            kinds(&mut lexer, r#"let s = "a \" b"; // note"#),
+            vec![
+                (SpanKind::Code, "let s = ".to_string()),
+                (SpanKind::String, r#""a \" b""#.to_string()),
+                (SpanKind::Code, "; ".to_string()),
+                (SpanKind::Comment, "// note".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, "x /* start"),
+            vec![
+                (SpanKind::Code, "x ".to_string()),
+                (SpanKind::Comment, "/* start".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, "end */ y"),
+            vec![
+                (SpanKind::Comment, "end */".to_string()),
+                (SpanKind::Code, " y".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn carries_python_docstrings_across_lines() {
+        let mut lexer = Lexer::new(&PYTHON);
+        assert_eq!(
+            kinds(&mut lexer, "x = \"\"\"doc"),
+            vec![
+                (SpanKind::Code, "x = ".to_string()),
+                (SpanKind::String, "\"\"\"doc".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, "more\"\"\"  # c"),
+            vec![
+                (SpanKind::String, "more\"\"\"".to_string()),
+                (SpanKind::Code, "  ".to_string()),
+                (SpanKind::Comment, "# c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_rust_char_literals_and_raw_strings() {
+        let mut lexer = Lexer::new(&RUST);
+        assert_eq!(
+            kinds(&mut lexer, r#"let q = '"'; // TODO"#),
+            vec![
+                (SpanKind::Code, "let q = ".to_string()),
+                (SpanKind::String, "'\"'".to_string()),
+                (SpanKind::Code, "; ".to_string()),
+                (SpanKind::Comment, "// TODO".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, r"fn f<'a>(s: &'a str) -> char { '\'' }"),
+            vec![
+                (
+                    SpanKind::Code,
+                    "fn f<'a>(s: &'a str) -> char { ".to_string()
+                ),
+                (SpanKind::String, r"'\''".to_string()),
+                (SpanKind::Code, " }".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, r###"let re = r#"a "// b" c"#; // d"###),
+            vec![
+                (SpanKind::Code, "let re = ".to_string()),
+                (SpanKind::String, r###"r#"a "// b" c"#"###.to_string()),
+                (SpanKind::Code, "; ".to_string()),
+                (SpanKind::Comment, "// d".to_string()),
+            ]
+        );
+        // Raw strings may span lines
+        assert_eq!(
+            kinds(&mut lexer, r#"let s = br"one"#),
+            vec![
+                (SpanKind::Code, "let s = ".to_string()),
+                (SpanKind::String, r#"br"one"#.to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, r#"// two" + x"#),
+            vec![
+                (SpanKind::String, r#"// two""#.to_string()),
+                (SpanKind::Code, " + x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_lua_block_comments() {
+        let mut lexer = Lexer::for_path(Path::new("init.lua")).unwrap();
+        assert_eq!(
+            kinds(&mut lexer, "x = 1 --[[ start"),
+            vec![
+                (SpanKind::Code, "x = 1 ".to_string()),
+                (SpanKind::Comment, "--[[ start".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, "end ]] y = '--' -- c"),
+            vec![
+                (SpanKind::Comment, "end ]]".to_string()),
+                (SpanKind::Code, " y = ".to_string()),
+                (SpanKind::String, "'--'".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "-- c".to_string()),
+            ]
+        );
+        // C-style comments are not Lua
+        assert_eq!(
+            kinds(&mut lexer, "a = b /* c"),
+            vec![(SpanKind::Code, "a = b /* c".to_string())]
+        );
+    }
+
+    #[test]
+    fn handles_haskell_comments_and_chars() {
+        let mut lexer = Lexer::for_path(Path::new("Main.hs")).unwrap();
+        assert_eq!(
+            kinds(&mut lexer, "f x' = '\"' {- note"),
+            vec![
+                (SpanKind::Code, "f x' = ".to_string()),
+                (SpanKind::String, "'\"'".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "{- note".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&mut lexer, "-} main = putStrLn \"--\" -- c"),
+            vec![
+                (SpanKind::Comment, "-}".to_string()),
+                (SpanKind::Code, " main = putStrLn ".to_string()),
+                (SpanKind::String, "\"--\"".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "-- c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_sql_hash_and_markup() {
+        let mut lexer = Lexer::for_path(Path::new("schema.sql")).unwrap();
+        assert_eq!(
+            kinds(&mut lexer, "SELECT '--' /* a */ -- b"),
+            vec![
+                (SpanKind::Code, "SELECT ".to_string()),
+                (SpanKind::String, "'--'".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "/* a */".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "-- b".to_string()),
+            ]
+        );
+
+        let mut lexer = Lexer::for_path(Path::new("run.sh")).unwrap();
+        assert_eq!(
+            kinds(&mut lexer, "echo \"#1\" # c"),
+            vec![
+                (SpanKind::Code, "echo ".to_string()),
+                (SpanKind::String, "\"#1\"".to_string()),
+                (SpanKind::Code, " ".to_string()),
+                (SpanKind::Comment, "# c".to_string()),
+            ]
+        );
+
+        let mut lexer = Lexer::for_path(Path::new("index.html")).unwrap();
+        assert_eq!(
+            kinds(&mut lexer, "<p>\"x\"</p><!-- c -->"),
+            vec![
+                (SpanKind::Code, "<p>\"x\"</p>".to_string()),
+                (SpanKind::Comment, "<!-- c -->".to_string()),
+            ]
+        );
+    }
+}
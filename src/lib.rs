@@ -1,5 +1,10 @@
 pub mod config;
+pub mod date;
 pub mod diagnostic;
+pub mod editorconfig;
+pub mod encoding;
 pub mod git;
+pub mod lexer;
 pub mod rules;
 pub mod run;
+pub mod suppression;
@@ -1,9 +1,15 @@
 use clap::Parser;
 use confique::Config;
 use horton::config::Conf;
+use horton::date;
 use horton::diagnostic;
+use horton::rules::never_edit;
+use horton::rules::pls_no_land::{self, TodoItem};
 use horton::rules::RULES;
-use horton::run::{Cli, OutputFormat, Run, Subcommands};
+use horton::run::{
+    Cli, NeverEditCommand, OutputFormat, Run, Subcommands, TodoColumn, TodosFormat, TodosGroupBy,
+};
+use horton::suppression;
 
 use anyhow::Context;
 use log::{debug, warn};
@@ -24,8 +30,12 @@ use log4rs::{
 /// re-enter serde_json just to say "nothing ran".
 const EMPTY_SARIF: &str = "{\"version\":\"2.1.0\",\"runs\":[]}";
 
+/// Columns are reported in UTF-16 code units, which is what VS Code and GitHub
+/// expect when rendering SARIF.
+const OUTPUT_COLUMN_UNIT: diagnostic::ColumnUnit = diagnostic::ColumnUnit::Utf16;
+
 fn generate_line_string(original_results: &diagnostic::Diagnostics) -> String {
-    return original_results
+    original_results
         .diagnostics
         .iter()
         .map(|d| {
@@ -39,7 +49,7 @@ fn generate_line_string(original_results: &diagnostic::Diagnostics) -> String {
             }
         })
         .collect::<Vec<String>>()
-        .join("\n");
+        .join("\n")
 }
 
 fn generate_sarif_string(
@@ -91,6 +101,9 @@ fn generate_sarif_string(
                 .unwrap(),
         )
         .results(results)
+        .column_kind(serde_json::to_value(
+            OUTPUT_COLUMN_UNIT.sarif_column_kind().unwrap().to_string(),
+        )?)
         .build()?;
     let sarif_built = sarif::SarifBuilder::default()
         .version("2.1.0")
@@ -113,13 +126,218 @@ fn find_toolbox_toml() -> Option<String> {
     None
 }
 
+/// Load the toolbox config, returning it along with the path it was loaded from.
+fn load_config() -> anyhow::Result<(Conf, String)> {
+    // If no configuration file is provided the default config will be used;
+    // some parts of toolbox can run with the default config.
+    let toolbox_toml: String = match find_toolbox_toml() {
+        Some(file) => file,
+        None => "no_config_found.toml".to_string(),
+    };
+
+    let config = Conf::builder()
+        .env()
+        .file(&toolbox_toml)
+        .load()
+        .with_context(|| format!("failed to load toolbox config from {:?}", toolbox_toml))?;
+
+    Ok((config, toolbox_toml))
+}
+
+fn never_edit_ls(path: Option<&str>, workspace: Option<&Path>) -> anyhow::Result<()> {
+    let (config, _) = load_config()?;
+    let listing = never_edit::list_protected(&config.neveredit, workspace, path)?;
+
+    for protected in &listing.protected {
+        println!("{}\t{}", protected.path, protected.glob);
+    }
+    for glob in &listing.unmatched {
+        println!("{:?} does not protect any existing files", glob);
+    }
+
+    Ok(())
+}
+
+fn todo_field(item: &TodoItem, column: TodoColumn) -> String {
+    match column {
+        TodoColumn::Path => item.path.clone(),
+        TodoColumn::Line => item.line.to_string(),
+        TodoColumn::Token => item.token.clone(),
+        TodoColumn::Owner => item.owner.clone().unwrap_or_default(),
+        TodoColumn::Ticket => item.ticket.clone().unwrap_or_default(),
+        TodoColumn::AgeDays => item.age_days.map(|a| a.to_string()).unwrap_or_default(),
+        TodoColumn::Text => item.text.clone(),
+    }
+}
+
+/// `item` as a JSON object with only the requested columns.
+fn todo_json(item: &TodoItem, columns: &[TodoColumn]) -> anyhow::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(item)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.retain(|key, _| columns.iter().any(|c| c.name() == key));
+    }
+    Ok(value)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn markdown_table(items: &[&TodoItem], columns: &[TodoColumn]) -> String {
+    let names: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+    let mut table = format!(
+        "| {} |\n|{}\n",
+        names.join(" | "),
+        " --- |".repeat(columns.len())
+    );
+    for item in items {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|&c| todo_field(item, c).replace('|', "\\|"))
+            .collect();
+        table.push_str(&format!("| {} |\n", fields.join(" | ")));
+    }
+    table
+}
+
+fn todos_report(
+    format: TodosFormat,
+    group_by: Option<TodosGroupBy>,
+    columns: &[TodoColumn],
+    workspace: Option<&Path>,
+    today: Option<&str>,
+) -> anyhow::Result<()> {
+    let (config, toolbox_toml) = load_config()?;
+    let today = match today {
+        Some(today) => date::parse_ymd(today)
+            .with_context(|| format!("--today {:?} is not a YYYY-MM-DD date", today))?,
+        None => date::today(),
+    };
+    let columns = if columns.is_empty() {
+        &TodoColumn::ALL[..]
+    } else {
+        columns
+    };
+    // Blaming every file is slow, so only do it when the age is shown
+    let blame = columns.contains(&TodoColumn::AgeDays);
+    let items = pls_no_land::todo_inventory(config, &toolbox_toml, workspace, today, blame)?;
+
+    // Groups keep the order of their first TODO; items without a key go under "(none)"
+    let mut groups: Vec<(String, Vec<&TodoItem>)> = Vec::new();
+    for item in &items {
+        let key = match group_by {
+            Some(TodosGroupBy::File) => Some(item.path.clone()),
+            Some(TodosGroupBy::Owner) => item.owner.clone(),
+            Some(TodosGroupBy::Ticket) => item.ticket.clone(),
+            None => Some(String::new()),
+        }
+        .unwrap_or_else(|| "(none)".to_string());
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((key, vec![item])),
+        }
+    }
+
+    let output = match format {
+        TodosFormat::Csv => {
+            let mut names: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+            if group_by.is_some() {
+                names.insert(0, "group");
+            }
+            let mut csv = names.join(",") + "\n";
+            for (key, group) in &groups {
+                for item in group {
+                    let mut fields: Vec<String> =
+                        columns.iter().map(|&c| todo_field(item, c)).collect();
+                    if group_by.is_some() {
+                        fields.insert(0, key.clone());
+                    }
+                    let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                    csv.push_str(&(fields.join(",") + "\n"));
+                }
+            }
+            csv
+        }
+        TodosFormat::Json => {
+            let doc = if group_by.is_some() {
+                serde_json::Value::Array(
+                    groups
+                        .iter()
+                        .map(|(key, group)| {
+                            let todos = group
+                                .iter()
+                                .map(|item| todo_json(item, columns))
+                                .collect::<anyhow::Result<Vec<_>>>()?;
+                            Ok(serde_json::json!({ "group": key, "todos": todos }))
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                )
+            } else {
+                serde_json::Value::Array(
+                    items
+                        .iter()
+                        .map(|item| todo_json(item, columns))
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                )
+            };
+            serde_json::to_string_pretty(&doc)? + "\n"
+        }
+        TodosFormat::Markdown => {
+            if group_by.is_some() {
+                groups
+                    .iter()
+                    .map(|(key, group)| {
+                        format!(
+                            "## {} ({})\n\n{}",
+                            key,
+                            group.len(),
+                            markdown_table(group, columns)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                markdown_table(&items.iter().collect::<Vec<_>>(), columns)
+            }
+        }
+    };
+    print!("{}", output);
+
+    Ok(())
+}
+
 fn run() -> anyhow::Result<()> {
     let start = Instant::now();
     let mut cli: Cli = Cli::parse();
 
-    if let Some(Subcommands::Genconfig {}) = &cli.subcommand {
-        Conf::print_default();
-        return Ok(());
+    match &cli.subcommand {
+        Some(Subcommands::Genconfig {}) => {
+            Conf::print_default();
+            return Ok(());
+        }
+        Some(Subcommands::NeverEdit {
+            command: NeverEditCommand::Ls { path },
+        }) => {
+            return never_edit_ls(path.as_deref(), cli.workspace.as_deref());
+        }
+        Some(Subcommands::Todos {
+            format,
+            group_by,
+            columns,
+        }) => {
+            return todos_report(
+                *format,
+                *group_by,
+                columns,
+                cli.workspace.as_deref(),
+                cli.today.as_deref(),
+            );
+        }
+        None => {}
     }
 
     cli.cache_dir = validate_cache_dir(cli.cache_dir);
@@ -171,21 +389,16 @@ fn run() -> anyhow::Result<()> {
 fn build_output(cli: Cli, start: &Instant) -> anyhow::Result<(String, Option<anyhow::Error>)> {
     let mut ret = diagnostic::Diagnostics::default();
 
-    // If no configuration file is provided the default config will be used;
-    // some parts of toolbox can run with the default config.
-    let toolbox_toml: String = match find_toolbox_toml() {
-        Some(file) => file,
-        None => "no_config_found.toml".to_string(),
-    };
-
-    let config = Conf::builder()
-        .env()
-        .file(&toolbox_toml)
-        .load()
-        .with_context(|| format!("failed to load toolbox config from {:?}", toolbox_toml))?;
+    let (config, toolbox_toml) = load_config()?;
 
     let upstream_mode = cli.upstream_mode || cli.cache_dir.ends_with("-upstream");
 
+    let today = match &cli.today {
+        Some(today) => date::parse_ymd(today)
+            .with_context(|| format!("--today {:?} is not a YYYY-MM-DD date", today))?,
+        None => date::today(),
+    };
+
     let run = Run {
         paths: cli.files.into_iter().map(PathBuf::from).collect(),
         config,
@@ -193,6 +406,7 @@ fn build_output(cli: Cli, start: &Instant) -> anyhow::Result<(String, Option<any
         cache_dir: cli.cache_dir.clone(),
         upstream_mode,
         workspace: cli.workspace,
+        today,
     };
 
     let mut results: Vec<anyhow::Result<Vec<diagnostic::Diagnostic>>> =
@@ -232,6 +446,27 @@ fn build_output(cli: Cli, start: &Instant) -> anyhow::Result<(String, Option<any
         }
     }
 
+    // Rules report columns in whatever unit is natural to them; suppression
+    // directives are located by byte offset, so compare in bytes
+    let mut columns = diagnostic::ColumnConverter::new(run.workspace());
+    columns.convert(&mut ret.diagnostics, diagnostic::ColumnUnit::Utf8);
+
+    let mut suppressor = suppression::Suppressor::new(run.workspace());
+    ret.diagnostics = suppressor.filter(ret.diagnostics);
+
+    // Ratchets count findings, so they only see the ones left after suppression
+    if !failed_rules.iter().any(|r| r == "pls_no_land") {
+        ret.diagnostics = pls_no_land::ratchet(&run, &cli.upstream, ret.diagnostics)?;
+    }
+
+    // Unused suppressions are only meaningful once every rule has reported; a
+    // failed rule would make its suppressions look unused.
+    if run.config.unusedsuppressions.enabled && failed_rules.is_empty() {
+        ret.diagnostics.extend(suppressor.unused(&run.paths));
+    }
+
+    columns.convert(&mut ret.diagnostics, OUTPUT_COLUMN_UNIT);
+
     let output_string = match cli.output_format {
         OutputFormat::Sarif => generate_sarif_string(&ret, &run, start)?,
         OutputFormat::Text => generate_line_string(&ret),
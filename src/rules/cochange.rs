@@ -0,0 +1,255 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::CoChangeConf;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::git;
+use crate::run::Run;
+
+use anyhow::Context;
+use glob_match::glob_match;
+use log::{debug, trace};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// A `$N` or `${N}` reference to a capture group of a `when` glob
+static GROUP_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$(?:(\d+)|\{(\d+)\})").unwrap());
+
+/// A `[[cochange]]` entry from the config with its `when` glob compiled.
+struct CoChangeRule {
+    when: String,
+    when_re: Regex,
+    require: String,
+    severity: Severity,
+}
+
+impl CoChangeRule {
+    fn compile(conf: &CoChangeConf) -> anyhow::Result<CoChangeRule> {
+        Ok(CoChangeRule {
+            when: conf.when.clone(),
+            when_re: glob_to_regex(&conf.when)
+                .with_context(|| format!("invalid cochange glob '{}'", conf.when))?,
+            require: conf.require.clone(),
+            severity: Severity::from_str(&conf.severity)
+                .with_context(|| format!("invalid severity for cochange '{}'", conf.when))?,
+        })
+    }
+}
+
+/// Regex matching the same paths as `glob`, with a capture group for each
+/// wildcard, character class and brace alternation, in order. `**/` captures
+/// the directories it matches including their trailing slash. Wildcards inside
+/// a brace alternation match as usual but are part of its single capture.
+fn glob_to_regex(glob: &str) -> anyhow::Result<Regex> {
+    Ok(Regex::new(&format!("^{}$", translate(glob, true)?))?)
+}
+
+/// Regex source for `glob`, with capturing or non-capturing groups.
+fn translate(glob: &str, capture: bool) -> anyhow::Result<String> {
+    let open = if capture { "(" } else { "(?:" };
+    let mut re = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str(&format!("{}(?:[^/]*/)*)", open));
+                } else {
+                    re.push_str(&format!("{}.*)", open));
+                }
+            }
+            '*' => re.push_str(&format!("{}[^/]*)", open)),
+            '?' => re.push_str(&format!("{}[^/])", open)),
+            '[' => {
+                re.push_str(open);
+                re.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push_str("])");
+            }
+            '{' => {
+                // Split at top-level commas up to the matching brace
+                let mut alternatives = vec![String::new()];
+                let mut depth = 0;
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    match c {
+                        '}' if depth == 0 => {
+                            closed = true;
+                            break;
+                        }
+                        ',' if depth == 0 => {
+                            alternatives.push(String::new());
+                            continue;
+                        }
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    alternatives.last_mut().unwrap().push(c);
+                }
+                if !closed {
+                    anyhow::bail!("unclosed '{{' in '{}'", glob);
+                }
+                let alternatives = alternatives
+                    .iter()
+                    .map(|alternative| translate(alternative, false))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                re.push_str(&format!("{}{})", open, alternatives.join("|")));
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    Ok(re)
+}
+
+/// `template` with each `$N` or `${N}` replaced by capture group N of `caps`.
+fn substitute(template: &str, caps: &Captures) -> String {
+    GROUP_RE
+        .replace_all(template, |group: &Captures| {
+            let index: usize = group
+                .get(1)
+                .or_else(|| group.get(2))
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(0);
+            caps.get(index).map_or("", |m| m.as_str()).to_string()
+        })
+        .into_owned()
+}
+
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+pub fn cochange(run: &Run, upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    if run.config.cochange.is_empty() {
+        trace!("no cochange rules configured");
+        return Ok(vec![]);
+    }
+
+    if run.is_upstream() {
+        trace!("'cochange' rule doesn't run on upstream");
+        return Ok(vec![]);
+    }
+
+    let rules = run
+        .config
+        .cochange
+        .iter()
+        .map(CoChangeRule::compile)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let modified = git::modified_since(upstream, run.workspace())?;
+    let mut changed: Vec<String> = modified.paths.keys().map(|p| normalize(p)).collect();
+    changed.sort();
+
+    debug!(
+        "checking {} cochange rules against {} changed files",
+        rules.len(),
+        changed.len()
+    );
+
+    // Only files in this run trigger rules, so each is reported once however
+    // the files are batched; any changed file can satisfy them
+    let mut triggers: Vec<String> = run
+        .paths
+        .iter()
+        .filter(|path| run.config.selects(&[], &[], path))
+        .map(|path| normalize(path))
+        .filter(|path| changed.contains(path))
+        .collect();
+    triggers.sort();
+
+    let mut diagnostics = Vec::new();
+    for rule in &rules {
+        for path in &triggers {
+            let Some(caps) = rule.when_re.captures(path) else {
+                continue;
+            };
+            let required = substitute(&rule.require, &caps);
+            if changed
+                .iter()
+                .any(|other| other != path && glob_match(&required, other))
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                path: path.clone(),
+                range: None,
+                severity: rule.severity.clone(),
+                code: "cochange-required".to_string(),
+                message: format!(
+                    "Expected a change in '{}' because '{}' was modified (cochange rule '{}')",
+                    required, path, rule.when
+                ),
+                replacements: None,
+            });
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_glob_captures() {
+        let re = glob_to_regex("src/**/*.rs").unwrap();
+        let caps = re.captures("src/net/http/client.rs").unwrap();
+        assert_eq!(
+            substitute("tests/$1${2}_test.rs", &caps),
+            "tests/net/http/client_test.rs"
+        );
+        let caps = re.captures("src/main.rs").unwrap();
+        assert_eq!(
+            substitute("tests/$1${2}_test.rs", &caps),
+            "tests/main_test.rs"
+        );
+
+        let re = glob_to_regex("proto/{api,internal}/*.proto").unwrap();
+        let caps = re.captures("proto/api/user.proto").unwrap();
+        assert_eq!(substitute("gen/$1/$2.pb.go", &caps), "gen/api/user.pb.go");
+        assert!(!re.is_match("proto/other/user.proto"));
+    }
+
+    #[test]
+    fn translates_wildcards_inside_braces() {
+        let re = glob_to_regex("web/{*.ts,*.tsx}").unwrap();
+        assert!(re.is_match("web/app.ts"));
+        assert!(re.is_match("web/app.tsx"));
+        assert!(!re.is_match("web/{*.ts,*.tsx}"));
+        assert!(!re.is_match("web/lib/app.ts"));
+
+        let re = glob_to_regex("{**/schema,api}/*.graphql").unwrap();
+        let caps = re.captures("packages/gql/schema/user.graphql").unwrap();
+        assert_eq!(substitute("$1|$2", &caps), "packages/gql/schema|user");
+        let caps = re.captures("api/order.graphql").unwrap();
+        assert_eq!(substitute("$1|$2", &caps), "api|order");
+
+        let re = glob_to_regex("{a,{b,c}*}.txt").unwrap();
+        assert!(re.is_match("a.txt"));
+        assert!(re.is_match("cat.txt"));
+        assert!(!re.is_match("d.txt"));
+
+        let err = glob_to_regex("src/{a,b").unwrap_err();
+        assert_eq!(err.to_string(), "unclosed '{' in 'src/{a,b'");
+    }
+}
@@ -35,10 +35,12 @@ impl IctcBlock {
             start: diagnostic::Position {
                 line: self.begin.unwrap(),
                 character: 0,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
             end: diagnostic::Position {
                 line: self.end.unwrap(),
                 character: 0,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
         }
     }
@@ -142,6 +144,7 @@ pub fn ictc(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnos
     let all_blocks: Vec<_> = run
         .paths
         .par_iter()
+        .filter(|file| run.config.selects(&config.include, &config.exclude, file))
         .filter_map(|file| find_ictc_blocks(file).ok())
         .flatten()
         .collect();
@@ -226,7 +229,7 @@ pub fn ictc(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnos
                         });
                     }
                     // If target file was not changed raise issue
-                    if blocks_by_path.get(&local_file).is_none() {
+                    if !blocks_by_path.contains_key(&local_file) {
                         diagnostics.push(diagnostic::Diagnostic {
                             path: block.path.to_str().unwrap().to_string(),
                             range: Some(block.get_range()),
@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use crate::config::LargeFileConf;
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
+use crate::git::{self, FileStatus};
+use crate::run::Run;
+
+use glob_match::glob_match;
+use log::{debug, trace};
+
+const LFS_ATTRIBUTES: &str = "filter=lfs diff=lfs merge=lfs -text";
+const GITATTRIBUTES: &str = ".gitattributes";
+
+/// gitattributes pattern for a single repository-relative file. Glob
+/// metacharacters are escaped so the pattern matches only this path, and
+/// patterns without a slash match in every directory, so root files are anchored.
+fn path_pattern(path: &str) -> String {
+    let mut pattern = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            ' ' => pattern.push_str("[[:space:]]"),
+            '[' | '*' | '?' | '\\' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            _ => pattern.push(c),
+        }
+    }
+    if pattern.contains('/') {
+        pattern
+    } else {
+        format!("/{}", pattern)
+    }
+}
+
+/// gitattributes pattern equivalent to a binary glob: `**/*.psd` becomes
+/// `*.psd`, which gitattributes already matches in every directory.
+fn glob_pattern(glob: &str) -> String {
+    match glob.strip_prefix("**/") {
+        Some(rest) if !rest.contains('/') => rest.to_string(),
+        _ => glob.to_string(),
+    }
+}
+
+fn lfs_line(pattern: &str) -> String {
+    format!("{} {}", pattern, LFS_ATTRIBUTES)
+}
+
+/// End of the workspace's .gitattributes, where fixes append their LFS lines.
+struct AttributesEnd {
+    position: Position,
+    /// Whether the file is empty or ends in a newline
+    terminated: bool,
+}
+
+impl AttributesEnd {
+    fn read(root: &Path) -> anyhow::Result<AttributesEnd> {
+        let text = match std::fs::read_to_string(root.join(GITATTRIBUTES)) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let terminated = text.is_empty() || text.ends_with('\n');
+        let line = text.matches('\n').count();
+        let character = text.rsplit('\n').next().map_or(0, str::len);
+        Ok(AttributesEnd {
+            position: Position {
+                line: line as u64,
+                character: character as u64,
+                unit: ColumnUnit::Utf8,
+            },
+            terminated,
+        })
+    }
+
+    /// Insertion of `lfs_line(pattern)` as the last line of .gitattributes.
+    fn append(&self, pattern: &str) -> Replacement {
+        let separator = if self.terminated { "" } else { "\n" };
+        Replacement {
+            path: Some(GITATTRIBUTES.to_string()),
+            deleted_region: Range {
+                start: self.position.clone(),
+                end: self.position.clone(),
+            },
+            inserted_content: format!("{}{}\n", separator, lfs_line(pattern)),
+        }
+    }
+}
+
+pub fn large_file(run: &Run, upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.largefile;
+
+    if !config.enabled {
+        trace!("'largefile' is disabled");
+        return Ok(vec![]);
+    }
+
+    if run.is_upstream() {
+        trace!("'largefile' skipped on upstream baseline run");
+        return Ok(vec![]);
+    }
+
+    debug!("scanning {} files for large files", run.paths.len());
+
+    let mut candidates: Vec<&Path> = run
+        .paths
+        .iter()
+        .map(|path| path.as_path())
+        .filter(|path| path.is_file())
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .collect();
+    candidates.sort();
+
+    // Fast exit before calling into git
+    if candidates.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let modified = git::modified_since(upstream, run.workspace())?;
+    let attributes = AttributesEnd::read(&run.root())?;
+
+    let mut ret = Vec::new();
+    for path in candidates {
+        if git::is_lfs_tracked(path, run.workspace())? {
+            continue;
+        }
+        if let Some(diagnostic) =
+            large_file_impl(path, modified.paths.get(path), config, &attributes)?
+        {
+            ret.push(diagnostic);
+        }
+    }
+
+    Ok(ret)
+}
+
+fn large_file_impl(
+    path: &Path,
+    status: Option<&FileStatus>,
+    config: &LargeFileConf,
+    attributes: &AttributesEnd,
+) -> anyhow::Result<Option<Diagnostic>> {
+    let path_str = path.to_string_lossy().replace('\\', "/");
+    let diagnostic = |code: &str, message: String, pattern: &str| Diagnostic {
+        path: path.to_string_lossy().to_string(),
+        range: None,
+        severity: Severity::Error,
+        code: code.to_string(),
+        message,
+        replacements: Some(vec![attributes.append(pattern)]),
+    };
+
+    // Files already in the repository are grandfathered in; only new ones are held to the limit
+    if status == Some(&FileStatus::Added) {
+        let size = std::fs::metadata(path)?.len();
+        if size > config.max_kb * 1024 {
+            let pattern = path_pattern(&path_str);
+            return Ok(Some(diagnostic(
+                "large-file",
+                format!(
+                    "New file is {} KB, over the {} KB limit; track it with Git LFS by adding '{}' to .gitattributes",
+                    size.div_ceil(1024),
+                    config.max_kb,
+                    lfs_line(&pattern)
+                ),
+                &pattern,
+            )));
+        }
+    }
+
+    if let Some(glob) = config
+        .binary_globs
+        .iter()
+        .find(|glob| glob_match(glob, &path_str))
+    {
+        let pattern = glob_pattern(glob);
+        return Ok(Some(diagnostic(
+            "binary-file",
+            format!(
+                "Binary file matches '{}' but is not tracked by Git LFS; add '{}' to .gitattributes",
+                glob,
+                lfs_line(&pattern)
+            ),
+            &pattern,
+        )));
+    }
+
+    Ok(None)
+}
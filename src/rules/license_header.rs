@@ -0,0 +1,372 @@
+use std::path::Path;
+
+use crate::config::{matches_any, LicenseHeaderConf};
+use crate::date;
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
+use crate::encoding::{self, Contents};
+use crate::git::{self, FileChanges, FileStatus};
+use crate::lexer;
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// A year, or a range of years such as `2019-2024`
+const YEAR_PATTERN: &str = r"\d{4}(?:\s*-\s*\d{4})?";
+
+/// Lines past the header's own length in which it may start, to allow for
+/// shebangs, encoding declarations and blank lines.
+const SEARCH_SLACK: usize = 10;
+
+/// Stripped from both ends of each line before comparing it with the template
+const COMMENT_MARKERS: &[&str] = &["<!--", "-->", "/*", "*/", "//", "--", "#", ";", "*"];
+
+static ENCODING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(#|//|--).*coding[:=]").unwrap());
+
+/// The template compiled to one regex per non-blank line.
+struct Header {
+    lines: Vec<Regex>,
+}
+
+impl Header {
+    fn compile(template: &str) -> anyhow::Result<Header> {
+        let mut seen_year = false;
+        let lines = template
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let mut pattern = regex::escape(l).replace(r"\{holder\}", ".+?");
+                // Only the first year is captured, to check whether it's stale
+                while let Some(at) = pattern.find(r"\{year\}") {
+                    let year = if seen_year {
+                        format!("(?:{})", YEAR_PATTERN)
+                    } else {
+                        format!("(?P<year>{})", YEAR_PATTERN)
+                    };
+                    seen_year = true;
+                    pattern.replace_range(at..at + r"\{year\}".len(), &year);
+                }
+                Regex::new(&format!("^{}$", pattern))
+                    .with_context(|| format!("invalid licenseheader.template line {:?}", l))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Header { lines })
+    }
+}
+
+/// How to write the header as a comment in a particular file.
+struct CommentStyle {
+    prefix: String,
+    start: Option<String>,
+    end: Option<String>,
+}
+
+fn comment_style(path: &Path, config: &LicenseHeaderConf) -> Option<CommentStyle> {
+    if let Some(style) = config
+        .styles
+        .iter()
+        .find(|s| matches_any(std::slice::from_ref(&s.glob), path))
+    {
+        return Some(CommentStyle {
+            prefix: style.prefix.clone(),
+            start: style.start.clone(),
+            end: style.end.clone(),
+        });
+    }
+    let lang = lexer::language_for(path)?;
+    if let Some(marker) = lang.line_comment() {
+        return Some(CommentStyle {
+            prefix: format!("{} ", marker),
+            start: None,
+            end: None,
+        });
+    }
+    let (start, end) = lang.block_comment()?;
+    Some(if start == "/*" {
+        CommentStyle {
+            prefix: " * ".to_string(),
+            start: Some("/*".to_string()),
+            end: Some(" */".to_string()),
+        }
+    } else {
+        CommentStyle {
+            prefix: String::new(),
+            start: Some(start.to_string()),
+            end: Some(end.to_string()),
+        }
+    })
+}
+
+/// `line` without surrounding whitespace and comment markers, and the byte
+/// offset in `line` where the result starts.
+fn strip_comment(line: &str) -> (usize, &str) {
+    let mut rest = line;
+    loop {
+        let trimmed = rest.trim_start();
+        match COMMENT_MARKERS.iter().find_map(|m| trimmed.strip_prefix(m)) {
+            Some(stripped) => rest = stripped,
+            None => {
+                rest = trimmed;
+                break;
+            }
+        }
+    }
+    let offset = line.len() - rest.len();
+    loop {
+        let trimmed = rest.trim_end();
+        match COMMENT_MARKERS.iter().find_map(|m| trimmed.strip_suffix(m)) {
+            Some(stripped) => rest = stripped,
+            None => return (offset, trimmed),
+        }
+    }
+}
+
+/// Where the header's year is, if the header is present: the line, and the
+/// byte range of the year within it. The inner `None` means the header has
+/// no `{year}`.
+#[allow(clippy::type_complexity)]
+fn find_header(lines: &[&str], header: &Header) -> Option<Option<(usize, usize, usize)>> {
+    let window = lines.len().min(header.lines.len() + SEARCH_SLACK);
+    // Non-blank lines with their index and the offset of their stripped content
+    let stripped: Vec<(usize, usize, &str)> = lines[..window]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let (offset, content) = strip_comment(line);
+            (i, offset, content)
+        })
+        .filter(|(_, _, content)| !content.is_empty())
+        .collect();
+
+    stripped.windows(header.lines.len()).find_map(|candidate| {
+        let mut year = None;
+        for ((i, offset, content), re) in candidate.iter().zip(&header.lines) {
+            let caps = re.captures(content)?;
+            if let Some(m) = caps.name("year") {
+                year = Some((*i, offset + m.start(), offset + m.end()));
+            }
+        }
+        Some(year)
+    })
+}
+
+/// Index of the first line after any shebang, encoding declaration or XML declaration.
+fn insertion_line(lines: &[&str]) -> usize {
+    let mut at = 0;
+    if lines
+        .first()
+        .is_some_and(|l| l.starts_with("#!") || l.starts_with("<?xml"))
+    {
+        at += 1;
+    }
+    if lines.get(at).is_some_and(|l| ENCODING_RE.is_match(l)) && at < 2 {
+        at += 1;
+    }
+    at
+}
+
+fn render(template: &str, holder: &str, year: i64, style: &CommentStyle, newline: &str) -> String {
+    let mut out = Vec::new();
+    if let Some(start) = &style.start {
+        out.push(start.clone());
+    }
+    for line in template.trim_matches('\n').lines() {
+        let line = line
+            .replace("{year}", &year.to_string())
+            .replace("{holder}", holder);
+        out.push(format!("{}{}", style.prefix, line).trim_end().to_string());
+    }
+    if let Some(end) = &style.end {
+        out.push(end.clone());
+    }
+    out.join(newline) + newline
+}
+
+pub fn license_header(run: &Run, upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.licenseheader;
+
+    if !config.enabled {
+        trace!("'licenseheader' is disabled");
+        return Ok(vec![]);
+    }
+
+    if config.template.trim().is_empty() {
+        return Ok(vec![Diagnostic {
+            path: run.config_path.clone(),
+            range: None,
+            severity: Severity::Warning,
+            code: "license-header-config".to_string(),
+            message: "no header template provided in config".to_string(),
+            replacements: None,
+        }]);
+    }
+
+    let header = Header::compile(&config.template)?;
+
+    // Years are only brought up to date in files being changed anyway
+    let modified = if run.is_upstream() {
+        FileChanges::default()
+    } else {
+        git::modified_since(upstream, run.workspace())?
+    };
+
+    debug!("scanning {} files for license headers", run.paths.len());
+
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| {
+            let changed = modified.paths.get(path.as_path()) == Some(&FileStatus::Modified);
+            license_header_impl(path, config, &header, changed, date::year(run.today))
+        })
+        .collect();
+
+    match results {
+        Ok(v) => Ok(v.into_iter().flatten().collect()),
+        Err(e) => Err(e),
+    }
+}
+
+fn license_header_impl(
+    path: &Path,
+    config: &LicenseHeaderConf,
+    header: &Header,
+    changed: bool,
+    year: i64,
+) -> anyhow::Result<Vec<Diagnostic>> {
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let (text, encoding) = match contents {
+        Contents::Text { text, encoding } => (text, encoding),
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "license_header", &reason)]);
+        }
+    };
+
+    trace!("scanning contents of {}", path.display());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let position = |line: usize, character: usize| Position {
+        line: line as u64,
+        character: character as u64,
+        unit: ColumnUnit::Utf8,
+    };
+
+    match find_header(&lines, header) {
+        None => {
+            let at = insertion_line(&lines);
+            let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+            let holder = match &config.holder {
+                Some(holder) => Some(holder.as_str()),
+                None if !config.template.contains("{holder}") => Some(""),
+                None => None,
+            };
+            let fix = match (holder, comment_style(path, config)) {
+                // Fixes computed on transcoded text would not line up with the file on disk
+                (Some(holder), Some(style)) if encoding.is_utf8() => {
+                    let mut inserted = render(&config.template, holder, year, &style, newline);
+                    if lines.get(at).is_some_and(|l| !l.trim().is_empty()) {
+                        inserted.push_str(newline);
+                    }
+                    Some(vec![Replacement {
+                        path: None,
+                        deleted_region: Range {
+                            start: position(at, 0),
+                            end: position(at, 0),
+                        },
+                        inserted_content: inserted,
+                    }])
+                }
+                _ => None,
+            };
+            Ok(vec![Diagnostic {
+                path: path.to_string_lossy().to_string(),
+                range: Some(Range {
+                    start: position(at, 0),
+                    end: position(at, 0),
+                }),
+                severity: Severity::Error,
+                code: "license-header-missing".to_string(),
+                message: "File is missing the license header".to_string(),
+                replacements: fix,
+            }])
+        }
+        Some(Some((line, start, end))) if changed => {
+            let found = &lines[line][start..end];
+            let (first, last) = match found.split_once('-') {
+                Some((first, last)) => (first.trim(), last.trim()),
+                None => (found, found),
+            };
+            if last.parse::<i64>().is_ok_and(|last| last >= year) {
+                return Ok(vec![]);
+            }
+            let updated = format!("{}-{}", first, year);
+            let range = Range {
+                start: position(line, start),
+                end: position(line, end),
+            };
+            Ok(vec![Diagnostic {
+                path: path.to_string_lossy().to_string(),
+                range: Some(range.clone()),
+                severity: Severity::Warning,
+                code: "license-header-stale-year".to_string(),
+                message: format!(
+                    "Copyright year '{}' is out of date; expected '{}'",
+                    found, updated
+                ),
+                replacements: encoding.is_utf8().then(|| {
+                    vec![Replacement {
+                        path: None,
+                        deleted_region: range,
+                        inserted_content: updated,
+                    }]
+                }),
+            }])
+        }
+        Some(_) => Ok(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_commented_headers() {
+        let header =
+            Header::compile("Copyright {year} {holder}\nSPDX-License-Identifier: MIT\n").unwrap();
+        let lines = [
+            "#!/usr/bin/env python3",
+            "# Copyright 2019-2024 Acme Corp",
+            "# SPDX-License-Identifier: MIT",
+            "import os",
+        ];
+        assert_eq!(find_header(&lines, &header), Some(Some((1, 12, 21))));
+        assert_eq!(insertion_line(&lines), 1);
+
+        let block = [
+            "/*",
+            " * Copyright 2020 Acme",
+            " * SPDX-License-Identifier: MIT",
+            " */",
+        ];
+        assert_eq!(find_header(&block, &header), Some(Some((1, 13, 17))));
+
+        assert_eq!(find_header(&["// Copyright Acme"], &header), None);
+    }
+}
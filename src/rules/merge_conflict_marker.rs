@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Severity};
+use crate::encoding::{self, Contents};
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    /// `<<<<<<< ours`
+    Start,
+    /// `||||||| base`, only present in diff3 style conflicts
+    Base,
+    /// `=======`
+    Separator,
+    /// `>>>>>>> theirs`
+    End,
+}
+
+/// The conflict marker `line` consists of, if any. Markers are exactly seven
+/// characters, optionally followed by a space and a label (none for `=======`).
+fn marker(line: &str) -> Option<Marker> {
+    let line = line.trim_end();
+    let labelled = |prefix: &str| {
+        line.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    };
+    if labelled("<<<<<<<") {
+        Some(Marker::Start)
+    } else if labelled("|||||||") {
+        Some(Marker::Base)
+    } else if line == "=======" {
+        Some(Marker::Separator)
+    } else if labelled(">>>>>>>") {
+        Some(Marker::End)
+    } else {
+        None
+    }
+}
+
+/// 0-indexed first and last lines of every complete conflict hunk in `lines`.
+/// A lone `=======`, such as a reStructuredText heading underline, is not a
+/// conflict.
+fn conflict_hunks<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<(usize, usize)> {
+    let mut hunks = Vec::new();
+    // Line of the current hunk's start marker, and the last marker seen in it
+    let mut open: Option<(usize, Marker)> = None;
+
+    for (i, line) in lines.into_iter().enumerate() {
+        let Some(marker) = marker(line) else {
+            continue;
+        };
+        open = match (open, marker) {
+            // A new start abandons an unfinished hunk
+            (_, Marker::Start) => Some((i, Marker::Start)),
+            (Some((start, Marker::Start)), Marker::Base) => Some((start, Marker::Base)),
+            (Some((start, Marker::Start | Marker::Base)), Marker::Separator) => {
+                Some((start, Marker::Separator))
+            }
+            (Some((start, Marker::Separator)), Marker::End) => {
+                hunks.push((start, i));
+                None
+            }
+            (open, _) => open,
+        };
+    }
+
+    hunks
+}
+
+pub fn merge_conflict_marker(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.mergeconflict;
+
+    if !config.enabled {
+        trace!("'mergeconflict' is disabled");
+        return Ok(vec![]);
+    }
+
+    debug!(
+        "scanning {} files for merge conflict markers",
+        run.paths.len()
+    );
+
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| merge_conflict_marker_impl(path))
+        .collect();
+
+    match results {
+        Ok(v) => Ok(v.into_iter().flatten().collect()),
+        Err(e) => Err(e),
+    }
+}
+
+fn merge_conflict_marker_impl(path: &Path) -> anyhow::Result<Vec<Diagnostic>> {
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let text = match contents {
+        Contents::Text { text, .. } => text,
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(
+                path,
+                "merge_conflict_marker",
+                &reason,
+            )]);
+        }
+    };
+
+    trace!("scanning contents of {}", path.display());
+
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(conflict_hunks(lines.iter().copied())
+        .into_iter()
+        .map(|(start, end)| Diagnostic {
+            path: path.to_string_lossy().to_string(),
+            range: Some(Range {
+                start: Position {
+                    line: start as u64,
+                    character: 0,
+                    unit: ColumnUnit::Utf8,
+                },
+                end: Position {
+                    line: end as u64,
+                    character: lines[end].len() as u64,
+                    unit: ColumnUnit::Utf8,
+                },
+            }),
+            severity: Severity::Error,
+            code: "merge-conflict-marker".to_string(),
+            message: format!(
+                "Unresolved merge conflict on lines {}-{}",
+                start + 1,
+                end + 1
+            ),
+            replacements: None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_complete_hunks_only() {
+        let text = "Title\n=======\n\n<<<<<<< HEAD\na: 1\n||||||| base\na: 0\n=======\na: 2\n>>>>>>> feature\n>>>>>>> stray\n<<<<<<< HEAD\nunfinished\n";
+        assert_eq!(conflict_hunks(text.lines()), vec![(3, 9)]);
+    }
+}
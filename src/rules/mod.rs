@@ -1,10 +1,19 @@
 use crate::diagnostic;
 use crate::run::Run;
 
+pub mod cochange;
 pub mod if_change_then_change;
+pub mod large_file;
+pub mod license_header;
+pub mod merge_conflict_marker;
+pub mod naming;
 pub mod never_edit;
 pub mod no_curly_quotes;
 pub mod pls_no_land;
+pub mod portable_paths;
+pub mod secrets;
+pub mod unicode_hygiene;
+pub mod whitespace;
 
 pub type RuleFn = fn(&Run, &str) -> anyhow::Result<Vec<diagnostic::Diagnostic>>;
 
@@ -13,4 +22,16 @@ pub const RULES: &[(&str, RuleFn)] = &[
     ("if_change_then_change", if_change_then_change::ictc),
     ("never_edit", never_edit::never_edit),
     ("no_curly_quotes", no_curly_quotes::no_curly_quotes),
+    ("unicode_hygiene", unicode_hygiene::unicode_hygiene),
+    ("whitespace", whitespace::whitespace),
+    (
+        "merge_conflict_marker",
+        merge_conflict_marker::merge_conflict_marker,
+    ),
+    ("large_file", large_file::large_file),
+    ("secrets", secrets::secrets),
+    ("license_header", license_header::license_header),
+    ("portable_paths", portable_paths::portable_paths),
+    ("naming", naming::naming),
+    ("cochange", cochange::cochange),
 ];
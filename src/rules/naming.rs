@@ -0,0 +1,305 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::{matches_any, NameCase, NamingConf};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static KEBAB_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap());
+static SNAKE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9]+(_[a-z0-9]+)*$").unwrap());
+static PASCAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Z][A-Za-z0-9]*$").unwrap());
+static CAMEL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-z][A-Za-z0-9]*$").unwrap());
+
+/// A `[[naming]]` entry from the config with its pattern compiled.
+struct NamingRule {
+    glob: String,
+    pattern: Option<Regex>,
+    case: Option<NameCase>,
+    severity: Severity,
+}
+
+impl NamingRule {
+    fn compile(conf: &NamingConf) -> anyhow::Result<NamingRule> {
+        Ok(NamingRule {
+            glob: conf.glob.clone(),
+            pattern: match &conf.pattern {
+                Some(pattern) => Some(
+                    Regex::new(pattern)
+                        .with_context(|| format!("invalid pattern for naming '{}'", conf.glob))?,
+                ),
+                None => None,
+            },
+            case: conf.case,
+            severity: Severity::from_str(&conf.severity)
+                .with_context(|| format!("invalid severity for naming '{}'", conf.glob))?,
+        })
+    }
+}
+
+fn case_name(case: NameCase) -> &'static str {
+    match case {
+        NameCase::Kebab => "kebab-case",
+        NameCase::Snake => "snake_case",
+        NameCase::Pascal => "PascalCase",
+        NameCase::Camel => "camelCase",
+    }
+}
+
+fn is_case(stem: &str, case: NameCase) -> bool {
+    match case {
+        NameCase::Kebab => KEBAB_RE.is_match(stem),
+        NameCase::Snake => SNAKE_RE.is_match(stem),
+        NameCase::Pascal => PASCAL_RE.is_match(stem),
+        NameCase::Camel => CAMEL_RE.is_match(stem),
+    }
+}
+
+/// The words of `name`, split at separators and at lower-to-upper case
+/// changes. Acronyms stay together: `HTMLParser` is `HTML`, `Parser`.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn to_case(name: &str, case: NameCase) -> String {
+    let words = words(name);
+    match case {
+        NameCase::Kebab => words.join("-").to_lowercase(),
+        NameCase::Snake => words.join("_").to_lowercase(),
+        NameCase::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        NameCase::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                if i == 0 {
+                    w.to_lowercase()
+                } else {
+                    capitalize(w)
+                }
+            })
+            .collect(),
+    }
+}
+
+/// `file_name` split at its first dot, ignoring a leading one: `Button.test.tsx`
+/// is `Button` and `.test.tsx`.
+fn split_stem(file_name: &str) -> (&str, &str) {
+    match file_name.char_indices().skip(1).find(|&(_, c)| c == '.') {
+        Some((i, _)) => file_name.split_at(i),
+        None => (file_name, ""),
+    }
+}
+
+/// A name that follows `rule`, derived from `file_name`, if one can be found.
+/// Every candidate keeps the extension as written before trying it lowercased.
+fn suggest(file_name: &str, rule: &NamingRule) -> Option<String> {
+    let (stem, extension) = split_stem(file_name);
+    let cases = match rule.case {
+        Some(case) => vec![case],
+        // Without a case style, try each one in turn against the pattern
+        None => vec![
+            NameCase::Snake,
+            NameCase::Kebab,
+            NameCase::Pascal,
+            NameCase::Camel,
+        ],
+    };
+    let extensions = [extension.to_string(), extension.to_lowercase()];
+    cases
+        .into_iter()
+        .flat_map(|case| {
+            let stem = to_case(stem, case);
+            extensions
+                .iter()
+                .map(move |extension| format!("{}{}", stem, extension))
+        })
+        .find(|candidate| {
+            candidate != file_name
+                && rule
+                    .pattern
+                    .as_ref()
+                    .is_none_or(|re| re.is_match(candidate))
+        })
+}
+
+pub fn naming(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    if run.config.naming.is_empty() {
+        trace!("no naming conventions configured");
+        return Ok(vec![]);
+    }
+
+    let rules = run
+        .config
+        .naming
+        .iter()
+        .map(NamingRule::compile)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    debug!("checking {} file names", run.paths.len());
+
+    let mut paths: Vec<&Path> = run
+        .paths
+        .iter()
+        .map(|path| path.as_path())
+        .filter(|path| run.config.selects(&[], &[], path))
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .flat_map(|path| naming_impl(path, &rules))
+        .collect())
+}
+
+fn naming_impl(path: &Path, rules: &[NamingRule]) -> Vec<Diagnostic> {
+    let Some(file_name) = path.file_name().map(|f| f.to_string_lossy()) else {
+        return vec![];
+    };
+    let (stem, _) = split_stem(&file_name);
+
+    rules
+        .iter()
+        .filter(|rule| matches_any(std::slice::from_ref(&rule.glob), path))
+        .filter_map(|rule| {
+            let mut problems = Vec::new();
+            if let Some(case) = rule.case {
+                if !is_case(stem, case) {
+                    problems.push(format!("be {}", case_name(case)));
+                }
+            }
+            if let Some(re) = &rule.pattern {
+                if !re.is_match(&file_name) {
+                    problems.push(format!("match '{}'", re.as_str()));
+                }
+            }
+            if problems.is_empty() {
+                return None;
+            }
+            let mut message = format!(
+                "File name '{}' should {} (files matching '{}')",
+                file_name,
+                problems.join(" and "),
+                rule.glob
+            );
+            match suggest(&file_name, rule) {
+                Some(suggestion) => message.push_str(&format!("; rename it to '{}'", suggestion)),
+                None => message.push_str("; no rename could be derived from the current name"),
+            }
+            Some(Diagnostic {
+                path: path.to_string_lossy().to_string(),
+                range: None,
+                severity: rule.severity.clone(),
+                code: "naming-convention".to_string(),
+                message,
+                replacements: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_cases() {
+        assert_eq!(
+            to_case("user-profile_card", NameCase::Pascal),
+            "UserProfileCard"
+        );
+        assert_eq!(to_case("HTMLParser", NameCase::Snake), "html_parser");
+        assert_eq!(
+            to_case("parseV2Response", NameCase::Kebab),
+            "parse-v2-response"
+        );
+        assert_eq!(to_case("UserCard", NameCase::Camel), "userCard");
+        assert_eq!(split_stem("Button.test.tsx"), ("Button", ".test.tsx"));
+        assert_eq!(split_stem(".eslintrc"), (".eslintrc", ""));
+    }
+
+    fn rule(case: Option<NameCase>, pattern: Option<&str>) -> NamingRule {
+        NamingRule {
+            glob: "**".to_string(),
+            pattern: pattern.map(|p| Regex::new(p).unwrap()),
+            case,
+            severity: Severity::Error,
+        }
+    }
+
+    #[test]
+    fn suggests_pascal_case_components() {
+        let pascal = rule(Some(NameCase::Pascal), None);
+        assert_eq!(
+            suggest("user-card.tsx", &pascal).as_deref(),
+            Some("UserCard.tsx")
+        );
+        assert_eq!(
+            suggest("user_card.test.TSX", &pascal).as_deref(),
+            Some("UserCard.test.TSX")
+        );
+
+        let lowercase_extension = rule(Some(NameCase::Pascal), Some(r"\.tsx$"));
+        assert_eq!(
+            suggest("user-card.TSX", &lowercase_extension).as_deref(),
+            Some("UserCard.tsx")
+        );
+    }
+
+    #[test]
+    fn suggests_migration_names_only_when_derivable() {
+        let migration = rule(None, Some(r"^\d{14}_[a-z_]+\.sql$"));
+        assert_eq!(
+            suggest("20260101120000_AddUsers.sql", &migration).as_deref(),
+            Some("20260101120000_add_users.sql")
+        );
+        assert_eq!(
+            suggest("20260101120000-add-users.SQL", &migration).as_deref(),
+            Some("20260101120000_add_users.sql")
+        );
+        // Nothing in the name can stand in for the missing timestamp
+        assert_eq!(suggest("add_orders.sql", &migration), None);
+    }
+}
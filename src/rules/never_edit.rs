@@ -1,4 +1,4 @@
-use crate::config::NeverEditConf;
+use crate::config::{matches_any, NeverEditConf};
 use crate::git::FileStatus;
 use crate::run::Run;
 use glob_match::glob_match;
@@ -94,7 +94,16 @@ fn repo_relative_posix(file_path: &str, workdir: &Path) -> Option<String> {
         .map(|p| p.to_string_lossy().replace('\\', "/"))
 }
 
-fn matches_never_edit(rel_path: &str, config: &NeverEditConf, workdir: &Path) -> bool {
+/// Returns the configured glob (as written in the config) that protects
+/// `rel_path`, if any.
+fn matches_never_edit<'a>(
+    rel_path: &str,
+    config: &'a NeverEditConf,
+    workdir: &Path,
+) -> Option<&'a str> {
+    if matches_any(&config.exclude, Path::new(rel_path)) {
+        return None;
+    }
     for glob_path in &config.paths {
         let pat = normalize_never_edit_glob_pattern(glob_path, workdir);
         if glob_match(&pat, rel_path) {
@@ -104,10 +113,10 @@ fn matches_never_edit(rel_path: &str, config: &NeverEditConf, workdir: &Path) ->
                 pat,
                 rel_path
             );
-            return true;
+            return Some(glob_path);
         }
     }
-    false
+    None
 }
 
 pub fn is_never_edit(file_path: &str, config: &NeverEditConf) -> bool {
@@ -117,7 +126,73 @@ pub fn is_never_edit(file_path: &str, config: &NeverEditConf) -> bool {
     let Some(rel) = repo_relative_posix(file_path, &workdir) else {
         return false;
     };
-    matches_never_edit(&rel, config, &workdir)
+    matches_never_edit(&rel, config, &workdir).is_some()
+}
+
+/// A tracked file protected by never-edit, along with the glob that protects it.
+#[derive(Debug)]
+pub struct ProtectedFile {
+    pub path: String,
+    pub glob: String,
+}
+
+#[derive(Debug, Default)]
+pub struct NeverEditListing {
+    /// Tracked files protected by a configured glob, sorted by path
+    pub protected: Vec<ProtectedFile>,
+
+    /// Configured globs that do not protect any tracked file
+    pub unmatched: Vec<String>,
+}
+
+/// Walk the tracked files of the repository and report which of them are
+/// protected, and by which glob. When `filter` is set, only tracked files at or
+/// below that path are listed; unmatched globs are always computed against the
+/// whole repository.
+pub fn list_protected(
+    config: &NeverEditConf,
+    workspace: Option<&Path>,
+    filter: Option<&str>,
+) -> anyhow::Result<NeverEditListing> {
+    let workdir = git::repo_workdir(workspace)?;
+    let tracked: Vec<String> = git::tracked_files(workspace)?
+        .iter()
+        .map(|file| strip_leading_dot_slash(file))
+        .collect();
+
+    let filter = match filter {
+        Some(f) => Some(repo_relative_posix(f, &workdir).ok_or_else(|| {
+            anyhow::anyhow!("{:?} is not inside the repository at {:?}", f, workdir)
+        })?),
+        None => None,
+    };
+
+    let mut listing = NeverEditListing::default();
+
+    for glob_path in &config.paths {
+        let pat = normalize_never_edit_glob_pattern(glob_path, &workdir);
+        if !tracked.iter().any(|file| glob_match(&pat, file)) {
+            listing.unmatched.push(glob_path.clone());
+        }
+    }
+
+    for file in &tracked {
+        if let Some(prefix) = &filter {
+            if !prefix.is_empty() && file != prefix && !file.starts_with(&format!("{prefix}/")) {
+                continue;
+            }
+        }
+        if let Some(glob) = matches_never_edit(file, config, &workdir) {
+            listing.protected.push(ProtectedFile {
+                path: file.clone(),
+                glob: glob.to_string(),
+            });
+        }
+    }
+
+    listing.protected.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(listing)
 }
 
 pub fn never_edit(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
@@ -180,7 +255,9 @@ pub fn never_edit(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::D
         .filter_map(|file| {
             file.to_str().and_then(|file_str| {
                 let rel = repo_relative_posix(file_str, &workdir)?;
-                if matches_never_edit(&rel, config, &workdir) {
+                if matches_never_edit(&rel, config, &workdir).is_some()
+                    && !matches_any(&run.config.global.exclude, Path::new(&rel))
+                {
                     Some(rel)
                 } else {
                     None
@@ -272,10 +349,12 @@ fn build_restore_replacement(
             start: diagnostic::Position {
                 line: 0,
                 character: 0,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
             end: diagnostic::Position {
                 line: last_line_idx as u64,
                 character: last_line_len as u64,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
         }
     } else {
@@ -283,15 +362,18 @@ fn build_restore_replacement(
             start: diagnostic::Position {
                 line: 0,
                 character: 0,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
             end: diagnostic::Position {
                 line: 0,
                 character: 0,
+                unit: diagnostic::ColumnUnit::Utf8,
             },
         }
     };
 
     Some(vec![diagnostic::Replacement {
+        path: None,
         deleted_region,
         inserted_content: upstream_text,
     }])
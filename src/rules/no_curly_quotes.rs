@@ -1,8 +1,7 @@
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::config::CurlyQuotesMode;
+use crate::encoding::{self, Contents};
 use crate::run::Run;
 
 use anyhow::Context;
@@ -10,7 +9,7 @@ use log::debug;
 use log::trace;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::diagnostic::{Diagnostic, Position, Range, Replacement, Severity};
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
 
 pub fn no_curly_quotes(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
     let config = &run.config.nocurlyquotes;
@@ -23,7 +22,13 @@ pub fn no_curly_quotes(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnos
     debug!("scanning {} files for curly quotes", run.paths.len());
 
     // Scan files in parallel
-    let results: Result<Vec<_>, _> = run.paths.par_iter().map(no_curly_quotes_impl).collect();
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .filter(|path| config.mode == CurlyQuotesMode::All || !is_prose(path))
+        .map(no_curly_quotes_impl)
+        .collect();
 
     match results {
         Ok(v) => Ok(v.into_iter().flatten().collect()),
@@ -31,23 +36,38 @@ pub fn no_curly_quotes(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnos
     }
 }
 
+const PROSE_EXTENSIONS: [&str; 8] = [
+    "md", "markdown", "mdx", "rst", "txt", "text", "adoc", "asciidoc",
+];
+
+/// Whether `path` is a prose document, where typographic quotes are expected.
+fn is_prose(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
 const DOUBLE_CURLY_QUOTES: [char; 4] = ['\u{201C}', '\u{201D}', '\u{201E}', '\u{201F}'];
 const SINGLE_CURLY_QUOTES: [char; 2] = ['\u{2018}', '\u{2019}'];
 
 fn no_curly_quotes_impl(path: &PathBuf) -> anyhow::Result<Vec<Diagnostic>> {
-    let in_file = File::open(path).with_context(|| format!("failed to open: {:#?}", path))?;
-    let in_buf = BufReader::new(in_file);
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let (text, encoding) = match contents {
+        Contents::Text { text, encoding } => (text, encoding),
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "no_curly_quotes", &reason)]);
+        }
+    };
 
     trace!("scanning contents of {}", path.display());
 
-    let lines_view = in_buf
-        .lines()
-        .collect::<std::io::Result<Vec<String>>>()
-        .with_context(|| format!("failed to read lines of text from {:#?}", path))?;
-
     let mut ret = Vec::new();
 
-    for (i, line) in lines_view.iter().enumerate() {
+    for (i, line) in text.lines().enumerate() {
         let mut char_issues = Vec::new();
 
         for (pos, c) in line.char_indices() {
@@ -69,14 +89,17 @@ fn no_curly_quotes_impl(path: &PathBuf) -> anyhow::Result<Vec<Diagnostic>> {
         let replacements: Vec<Replacement> = char_issues
             .iter()
             .map(|&(char_pos, rchar)| Replacement {
+                path: None,
                 deleted_region: Range {
                     start: Position {
                         line: i as u64,
                         character: char_pos,
+                        unit: ColumnUnit::CodePoint,
                     },
                     end: Position {
                         line: i as u64,
                         character: char_pos + 1,
+                        unit: ColumnUnit::CodePoint,
                     },
                 },
                 inserted_content: rchar.to_string(),
@@ -89,16 +112,19 @@ fn no_curly_quotes_impl(path: &PathBuf) -> anyhow::Result<Vec<Diagnostic>> {
                 start: Position {
                     line: i as u64,
                     character: char_issues.first().unwrap().0,
+                    unit: ColumnUnit::CodePoint,
                 },
                 end: Position {
                     line: i as u64,
                     character: char_issues.last().unwrap().0 + 1,
+                    unit: ColumnUnit::CodePoint,
                 },
             }),
             severity: Severity::Error,
             code: "no-curly-quotes".to_string(),
             message: format!("Found curly quote on line {}", i + 1),
-            replacements: Some(replacements),
+            // Fixes computed on transcoded text would not line up with the file on disk
+            replacements: encoding.is_utf8().then_some(replacements),
         });
     }
 
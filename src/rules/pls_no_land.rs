@@ -1,154 +1,868 @@
 // trunk-ignore-all(trunk-toolbox/do-not-land,trunk-toolbox/todo)
+use crate::config::{all_spans, Conf, TodoConf, TokenConf};
+use crate::date;
 use crate::diagnostic;
-use crate::run::Run;
+use crate::encoding::{self, Contents};
+use crate::git;
+use crate::lexer::{self, Lexer, Span, SpanKind};
+use crate::run::{self, Run};
+use crate::suppression::FileSuppressions;
 use anyhow::Context;
 use log::{debug, trace};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use regex::Regex;
-use std::fs::File;
-use std::io::Read;
-use std::io::{BufRead, BufReader};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::LazyLock;
 
 static DNL_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)(DO[\s_-]*NOT[\s_-]*LAND)").unwrap());
 static TODO_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)(TODO|FIXME)(\W+.*)?$").unwrap());
+static TODO_ANNOTATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\(([^)]*)\)").unwrap());
+static EXPIRES_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:expires\s*=\s*)?(\d{4}-\d{2}-\d{2})$").unwrap());
+// Used to tell tickets from owners when no `require_ticket` pattern is configured
+static DEFAULT_TICKET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z][A-Z0-9]*-\d+$").unwrap());
 
-pub fn is_binary_file(path: &PathBuf) -> std::io::Result<bool> {
-    let mut file = File::open(path)?;
-    let mut buffer = [0; 4096];
-    let n = file.read(&mut buffer)?;
-    Ok(buffer[..n].contains(&0))
+/// Parenthesized annotation following a TODO marker, e.g. `TODO(alice, ABC-123)`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TodoAnnotation {
+    pub owner: Option<String>,
+    pub ticket: Option<String>,
+    /// `YYYY-MM-DD` deadline, from `TODO(2026-12-31)` or `TODO(expires=2026-12-31)`
+    pub expires: Option<String>,
 }
 
-pub fn is_ignored_file(path: &Path) -> bool {
-    // Filter out well known files that should have the word donotland in them (like toolbox.toml)
-    path.file_name().map_or(false, |f| f == "toolbox.toml")
+/// Parse the annotation at the start of `rest`, the text immediately following
+/// a TODO (or DO-NOT-LAND) marker. Comma-separated parts that look like a date
+/// are the expiry, parts matching `ticket_re` are tickets, and the first
+/// remaining part is the owner.
+pub fn parse_todo_annotation(rest: &str, ticket_re: Option<&Regex>) -> TodoAnnotation {
+    let mut annotation = TodoAnnotation::default();
+    let Some(caps) = TODO_ANNOTATION_RE.captures(rest) else {
+        return annotation;
+    };
+    let ticket_re = ticket_re.unwrap_or(&DEFAULT_TICKET_RE);
+
+    for part in caps[1].split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some(date) = EXPIRES_RE.captures(part) {
+            if annotation.expires.is_none() {
+                annotation.expires = Some(date[1].to_string());
+            }
+        } else if let Some(m) = ticket_re.find(part) {
+            if annotation.ticket.is_none() {
+                annotation.ticket = Some(m.as_str().to_string());
+            }
+        } else if annotation.owner.is_none() {
+            annotation.owner = Some(part.to_string());
+        }
+    }
+
+    annotation
+}
+
+/// A `[[tokens]]` entry from the config with its pattern compiled.
+struct TokenRule {
+    code: String,
+    re: Regex,
+    severity: diagnostic::Severity,
+    message: String,
+    paths: Vec<String>,
+    exclude: Vec<String>,
+    ratchet: bool,
+    spans: Vec<SpanKind>,
+}
+
+impl TokenRule {
+    fn compile(conf: &TokenConf) -> anyhow::Result<TokenRule> {
+        Ok(TokenRule {
+            code: conf.code.clone(),
+            re: Regex::new(&conf.pattern)
+                .with_context(|| format!("invalid pattern for token '{}'", conf.code))?,
+            severity: diagnostic::Severity::from_str(&conf.severity)
+                .with_context(|| format!("invalid severity for token '{}'", conf.code))?,
+            message: conf.message.clone(),
+            paths: conf.paths.clone(),
+            exclude: conf.exclude.clone(),
+            ratchet: conf.ratchet,
+            spans: conf.spans.clone(),
+        })
+    }
+}
+
+/// What a TODO must carry to be accepted. With no requirements every TODO is reported.
+struct TodoPolicy {
+    require_ticket: Option<Regex>,
+    require_owner: bool,
+    ticket_index: Option<TicketIndex>,
+    today: i64,
+    expiry_warning_days: i64,
+    ratchet: bool,
+    /// Report every TODO as `todo`, for `toolbox todos`
+    inventory: bool,
+}
+
+/// Open ticket IDs exported to a local file, so TODOs can be checked offline.
+struct TicketIndex {
+    path: String,
+    open: HashSet<String>,
+}
+
+impl TicketIndex {
+    fn load(path: &str, workspace: Option<&Path>) -> anyhow::Result<TicketIndex> {
+        let resolved = workspace.map_or_else(|| PathBuf::from(path), |w| w.join(path));
+        let content = std::fs::read_to_string(&resolved)
+            .with_context(|| format!("failed to read todo.ticket_index {:?}", resolved))?;
+        let open: HashSet<String> = serde_json::from_str::<Vec<String>>(&content)
+            .with_context(|| {
+                format!(
+                    "todo.ticket_index {:?} must be a JSON array of ticket IDs",
+                    resolved
+                )
+            })?
+            .into_iter()
+            .collect();
+        Ok(TicketIndex {
+            path: path.to_string(),
+            open,
+        })
+    }
+}
+
+impl TodoPolicy {
+    fn compile(conf: &TodoConf, run: &Run) -> anyhow::Result<TodoPolicy> {
+        let require_ticket = match &conf.require_ticket {
+            Some(pattern) => Some(
+                Regex::new(pattern)
+                    .with_context(|| format!("invalid todo.require_ticket {:?}", pattern))?,
+            ),
+            None => None,
+        };
+        let ticket_index = match &conf.ticket_index {
+            // Don't require the export to exist unless the todo rule will use it
+            Some(path) if conf.enabled => Some(TicketIndex::load(path, run.workspace())?),
+            _ => None,
+        };
+        Ok(TodoPolicy {
+            require_ticket,
+            require_owner: conf.require_owner,
+            ticket_index,
+            today: run.today,
+            expiry_warning_days: conf.expiry_warning_days as i64,
+            ratchet: conf.ratchet,
+            inventory: false,
+        })
+    }
+
+    /// Returns what to report for a TODO marker, or None if it is acceptable.
+    fn violation(
+        &self,
+        token: &str,
+        rest: &str,
+    ) -> Option<(diagnostic::Severity, &'static str, String)> {
+        if self.inventory {
+            return Some((
+                diagnostic::Severity::Note,
+                "todo",
+                format!("Found '{}'", token),
+            ));
+        }
+        let annotation = parse_todo_annotation(rest, self.require_ticket.as_ref());
+
+        let expires = annotation
+            .expires
+            .as_deref()
+            .and_then(|e| Some((e, date::parse_ymd(e)?)));
+        if let Some((expires, deadline)) = expires {
+            if self.today > deadline {
+                return Some((
+                    diagnostic::Severity::Error,
+                    "todo-expired",
+                    format!(
+                        "'{}' expired on {} ({} days overdue)",
+                        token,
+                        expires,
+                        self.today - deadline
+                    ),
+                ));
+            }
+            if deadline - self.today < self.expiry_warning_days {
+                return Some((
+                    diagnostic::Severity::Warning,
+                    "todo-expiring",
+                    format!(
+                        "'{}' expires on {} (in {} days)",
+                        token,
+                        expires,
+                        deadline - self.today
+                    ),
+                ));
+            }
+        }
+
+        // With only a ticket index, TODOs without a ticket are still plain TODOs
+        let indexed = self.ticket_index.is_some() && annotation.ticket.is_some();
+        if self.require_ticket.is_none() && !self.require_owner && !indexed {
+            // A deadline is enough to make a TODO acceptable until it passes
+            if expires.is_some() {
+                return None;
+            }
+            // Lower severity than DNL
+            return Some((
+                diagnostic::Severity::Warning,
+                "todo",
+                format!("Found '{}'", token),
+            ));
+        }
+
+        if let Some(re) = &self.require_ticket {
+            if annotation.ticket.is_none() {
+                return Some((
+                    diagnostic::Severity::Warning,
+                    "todo-missing-ticket",
+                    format!(
+                        "'{}' is missing a ticket reference matching {:?}",
+                        token,
+                        re.as_str()
+                    ),
+                ));
+            }
+        }
+        if self.require_owner && annotation.owner.is_none() {
+            return Some((
+                diagnostic::Severity::Warning,
+                "todo-missing-owner",
+                format!("'{}' is missing an owner, e.g. {}(name)", token, token),
+            ));
+        }
+        if let (Some(index), Some(ticket)) = (&self.ticket_index, &annotation.ticket) {
+            if !index.open.contains(ticket) {
+                return Some((
+                    diagnostic::Severity::Warning,
+                    "todo-stale-ticket",
+                    format!(
+                        "'{}' references {}, which is closed or unknown in {}",
+                        token, ticket, index.path
+                    ),
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Absolute path of the loaded config file, if there is one.
+fn config_file(run: &Run) -> Option<PathBuf> {
+    Some(run.config_path.as_str())
+        .filter(|path| !path.is_empty())
+        .and_then(|path| std::path::absolute(path).ok())
+}
+
+/// Everything compiled from the config once per run and shared by the per-file scans.
+struct Scanner {
+    tokens: Vec<TokenRule>,
+    todo: TodoPolicy,
+    dnl_spans: Vec<SpanKind>,
+    todo_spans: Vec<SpanKind>,
+    /// The loaded config, which spells out the markers it looks for
+    config_file: Option<PathBuf>,
+}
+
+impl Scanner {
+    fn compile(run: &Run) -> anyhow::Result<Scanner> {
+        Ok(Scanner {
+            tokens: run
+                .config
+                .tokens
+                .iter()
+                .map(TokenRule::compile)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            todo: TodoPolicy::compile(&run.config.todo, run)?,
+            dnl_spans: run.config.donotland.spans.clone(),
+            todo_spans: run.config.todo.spans.clone(),
+            config_file: config_file(run),
+        })
+    }
+
+    /// Whether any check is limited to some kinds of spans, so files need lexing.
+    fn needs_lexer(&self) -> bool {
+        let restricted = |spans: &[SpanKind]| all_spans().iter().any(|k| !spans.contains(k));
+        restricted(&self.dnl_spans)
+            || restricted(&self.todo_spans)
+            || self.tokens.iter().any(|t| restricted(&t.spans))
+    }
+
+    fn tokens_for(&self, path: &Path, config: &Conf) -> Vec<&TokenRule> {
+        self.tokens
+            .iter()
+            .filter(|t| config.selects(&t.paths, &t.exclude, path))
+            .collect()
+    }
+
+    /// Whether any enabled check looks at the contents of `path`.
+    fn selects(&self, path: &Path, config: &Conf) -> bool {
+        if self.is_config_file(path) {
+            return false;
+        }
+        (config.donotland.enabled
+            && config.selects(&config.donotland.include, &config.donotland.exclude, path))
+            || (config.todo.enabled
+                && config.selects(&config.todo.include, &config.todo.exclude, path))
+            || !self.tokens_for(path, config).is_empty()
+    }
+
+    fn is_config_file(&self, path: &Path) -> bool {
+        self.config_file
+            .as_deref()
+            .is_some_and(|config_file| std::path::absolute(path).is_ok_and(|p| p == config_file))
+    }
+
+    /// The ratchet family a diagnostic code is counted under, if that family is ratcheted.
+    fn ratchet_family<'a>(&'a self, code: &str) -> Option<&'a str> {
+        if self.todo.ratchet && (code == "todo" || code.starts_with("todo-")) {
+            return Some("todo");
+        }
+        self.tokens
+            .iter()
+            .find(|t| t.ratchet && t.code == code)
+            .map(|t| t.code.as_str())
+    }
 }
 
 // Checks for $re and other forms thereof in source code
 //
 // Note that this is named "pls_no_land" to avoid causing DNL matches everywhere in trunk-toolbox.
-pub fn pls_no_land(run: &Run, _upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
+pub fn pls_no_land(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
     let dnl_config = &run.config.donotland;
     let todo_config = &run.config.todo;
 
-    // Avoid opening the file if neither are enabled.
-    if !dnl_config.enabled && !todo_config.enabled {
+    // Avoid opening the file if nothing is enabled.
+    if !dnl_config.enabled && !todo_config.enabled && run.config.tokens.is_empty() {
         trace!("'donotland' is disabled");
         trace!("'todo' is disabled");
+        trace!("no custom tokens configured");
         return Ok(vec![]);
     }
 
+    let scanner = Scanner::compile(run)?;
+
+    let mut ret = Vec::new();
+    if dnl_config.enabled && dnl_config.commits && !run.is_upstream() {
+        ret.extend(dnl_history(run, upstream)?);
+    }
+
     debug!("scanning {} files for pls_no_land", run.paths.len());
 
     // Scan files in parallel
     let results: Result<Vec<_>, _> = run
         .paths
         .par_iter()
-        .map(|path| pls_no_land_impl(path, run))
+        .map(|path| pls_no_land_impl(path, run, &scanner))
         .collect();
 
     match results {
-        Ok(v) => Ok(v.into_iter().flatten().collect()),
+        Ok(v) => {
+            ret.extend(v.into_iter().flatten());
+            Ok(ret)
+        }
         Err(e) => Err(e),
     }
 }
 
-fn pls_no_land_impl(path: &PathBuf, run: &Run) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
-    let config: &crate::config::Conf = &run.config;
+/// Regex matching any of `tokens` as a whole word. Hyphens and underscores
+/// join words, so `dnl/experiment` matches `dnl` but `dnl-cleanup` does not.
+fn history_regex(tokens: &[String]) -> anyhow::Result<Regex> {
+    let mut tokens: Vec<&String> = tokens.iter().filter(|t| !t.is_empty()).collect();
+    // Prefer the longest token where several match at the same place
+    tokens.sort_by_key(|t| std::cmp::Reverse(t.len()));
+    let alternatives: Vec<String> = tokens.iter().map(|t| regex::escape(t)).collect();
+    Regex::new(&format!(
+        r"(?:^|[^\w-])({})(?:$|[^\w-])",
+        alternatives.join("|")
+    ))
+    .context("invalid donotland.commit_tokens")
+}
 
-    if is_binary_file(path).unwrap_or(true) {
-        debug!("ignoring binary file {}", path.display());
+/// Check the current branch name and every commit since the merge-base with
+/// `upstream` for DO-NOT-LAND markers.
+///
+/// The findings belong to no file, but are attached to the first changed file
+/// so that they are reported once, by whichever batch of files includes it.
+/// Deleted files and the config file, which is never scanned, can't be the anchor.
+fn dnl_history(run: &Run, upstream: &str) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
+    if run.config.donotland.commit_tokens.is_empty() {
         return Ok(vec![]);
     }
 
-    if is_ignored_file(path) {
-        debug!("ignoring ignored file {}", path.display());
+    let root = run.root();
+    let config_file = config_file(run);
+    let modified = git::modified_since(upstream, run.workspace())?;
+    let Some(anchor) = modified
+        .paths
+        .iter()
+        .filter(|(_, status)| **status != git::FileStatus::Deleted)
+        .filter(|(path, _)| config_file.as_deref() != Some(root.join(path).as_path()))
+        .map(|(path, _)| run::relative_path(&root, path))
+        .min()
+    else {
         return Ok(vec![]);
+    };
+    let Some(anchor) = run
+        .paths
+        .iter()
+        .find(|path| run::relative_path(&root, path) == anchor)
+    else {
+        trace!("commits are checked with {}", anchor.display());
+        return Ok(vec![]);
+    };
+    let anchor = anchor.to_string_lossy().to_string();
+
+    let re = history_regex(&run.config.donotland.commit_tokens)?;
+    let mut ret = Vec::new();
+
+    if let Some(branch) = git::current_branch(run.workspace())? {
+        if let Some(m) = re.captures(&branch).and_then(|c| c.get(1)) {
+            ret.push(diagnostic::Diagnostic {
+                path: anchor.clone(),
+                range: None,
+                severity: diagnostic::Severity::Error,
+                code: "do-not-land-commit".to_string(),
+                message: format!("Found '{}' in branch name '{}'", m.as_str(), branch),
+                replacements: None,
+            });
+        }
+    }
+
+    let commits = git::commits_since(upstream, run.workspace())
+        .with_context(|| format!("failed to list commits since {}", upstream))?;
+    debug!("scanning {} commit messages for do-not-land", commits.len());
+    for commit in commits {
+        if let Some(m) = re.captures(&commit.message).and_then(|c| c.get(1)) {
+            ret.push(diagnostic::Diagnostic {
+                path: anchor.clone(),
+                range: None,
+                severity: diagnostic::Severity::Error,
+                code: "do-not-land-commit".to_string(),
+                message: format!(
+                    "Found '{}' in commit {} \"{}\"",
+                    m.as_str(),
+                    &commit.sha[..12],
+                    commit.summary
+                ),
+                replacements: None,
+            });
+        }
+    }
+
+    Ok(ret)
+}
+
+fn pls_no_land_impl(
+    path: &Path,
+    run: &Run,
+    scanner: &Scanner,
+) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
+    let mut ret = Vec::new();
+
+    // Paths are checked even for binary files, e.g. a forgotten scratch_donotland.png
+    let config = &run.config.donotland;
+    if config.enabled
+        && !run.is_upstream()
+        && run.config.selects(&config.include, &config.exclude, path)
+        && path.exists()
+    {
+        // Only names inside the repository count, not wherever it is checked out
+        let relative = run::relative_path(&run.root(), path);
+        if let Some(m) = DNL_RE.find(&relative.to_string_lossy()) {
+            ret.push(diagnostic::Diagnostic {
+                path: path.to_str().unwrap().to_string(),
+                range: None,
+                severity: diagnostic::Severity::Error,
+                code: "do-not-land".to_string(),
+                message: format!("Found '{}' in file path", m.as_str()),
+                replacements: None,
+            });
+        }
     }
 
-    let in_file = File::open(path).with_context(|| format!("failed to open: {:#?}", path))?;
-    let mut in_buf = BufReader::new(in_file);
+    ret.extend(scan_file(path, run, scanner)?);
 
-    let mut first_line = vec![];
-    in_buf.read_until(b'\n', &mut first_line)?;
+    Ok(ret)
+}
 
-    if first_line.is_empty() {
+fn scan_file(
+    path: &Path,
+    run: &Run,
+    scanner: &Scanner,
+) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
+    if !scanner.selects(path, &run.config) {
+        debug!("ignoring excluded file {}", path.display());
         return Ok(vec![]);
     }
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let text = match contents {
+        Contents::Text { text, .. } => text,
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "pls_no_land", &reason)]);
+        }
+    };
 
     trace!("scanning contents of {}", path.display());
 
-    let first_line_view = String::from_utf8(first_line)
-        .with_context(|| format!("could not read first line of {:#?}", path))?;
-    let lines_view = in_buf
-        .lines()
-        .collect::<std::io::Result<Vec<String>>>()
-        .with_context(|| format!("failed to read lines of text from {:#?}", path))?;
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(scan_lines(path, &lines, run, scanner))
+}
+
+/// Replace the diagnostics of each ratcheted family in a file with a single
+/// `<family>-ratchet` diagnostic when there are more of them than upstream,
+/// and drop them otherwise. Runs after suppressions have been applied, so
+/// suppressed findings don't count on either side.
+pub fn ratchet(
+    run: &Run,
+    upstream: &str,
+    diagnostics: Vec<diagnostic::Diagnostic>,
+) -> anyhow::Result<Vec<diagnostic::Diagnostic>> {
+    let ratcheted = run.config.todo.ratchet || run.config.tokens.iter().any(|t| t.ratchet);
+    if !ratcheted || run.is_upstream() {
+        return Ok(diagnostics);
+    }
+    let scanner = Scanner::compile(run)?;
 
     let mut ret = Vec::new();
+    // path -> family -> (count, severity of the first occurrence)
+    let mut after: BTreeMap<String, BTreeMap<String, (usize, diagnostic::Severity)>> =
+        BTreeMap::new();
+    for d in diagnostics {
+        match scanner.ratchet_family(&d.code) {
+            Some(family) if !d.path.is_empty() => {
+                after
+                    .entry(d.path.clone())
+                    .or_default()
+                    .entry(family.to_string())
+                    .or_insert((0, d.severity.clone()))
+                    .0 += 1;
+            }
+            _ => ret.push(d),
+        }
+    }
 
-    for (i, line) in [first_line_view]
-        .iter()
-        .chain(lines_view.iter())
-        .enumerate()
-    {
-        // DO-NOT-LAND should always fire and not use HTL semantics. So only generate
-        // those warnings on the current code and skip the upstream
-        if !line.contains("trunk-ignore(|-begin|-end|-all)\\(trunk-toolbox/(do-not-land)\\)")
-            && config.donotland.enabled
-            && !run.is_upstream()
-        {
-            if let Some(m) = DNL_RE.find(line) {
+    for (path, families) in after {
+        let before = upstream_counts(Path::new(&path), run, upstream, &scanner);
+        for (family, (count, severity)) in families {
+            let previous = before.get(&family).copied().unwrap_or(0);
+            if count > previous {
                 ret.push(diagnostic::Diagnostic {
-                    path: path.to_str().unwrap().to_string(),
-                    range: Some(diagnostic::Range {
-                        start: diagnostic::Position {
-                            line: i as u64,
-                            character: m.start() as u64,
-                        },
-                        end: diagnostic::Position {
-                            line: i as u64,
-                            character: m.end() as u64,
-                        },
-                    }),
-                    severity: diagnostic::Severity::Error,
-                    code: "do-not-land".to_string(),
-                    message: format!("Found '{}'", m.as_str()),
+                    path: path.clone(),
+                    range: None,
+                    severity,
+                    code: format!("{}-ratchet", family),
+                    message: format!("{} count increased from {} to {}", family, previous, count),
                     replacements: None,
                 });
             }
         }
-        if !line.contains("trunk-ignore(|-begin|-end|-all)\\(trunk-toolbox/(todo)\\)")
-            && config.todo.enabled
-        {
-            if let Some(m) = TODO_RE.captures(line) {
+    }
+
+    Ok(ret)
+}
+
+/// Unsuppressed diagnostics of each ratcheted family in the upstream version of `path`.
+fn upstream_counts(
+    path: &Path,
+    run: &Run,
+    upstream: &str,
+    scanner: &Scanner,
+) -> HashMap<String, usize> {
+    // Files that don't exist upstream (or can't be read there) start from zero
+    let content = git::get_upstream_content(upstream, &path.to_string_lossy(), run.workspace())
+        .unwrap_or_default();
+    let suppressions = FileSuppressions::parse(path, &content);
+    let lines: Vec<&str> = content.lines().collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for d in scan_lines(path, &lines, run, scanner) {
+        if suppressions.within_directive(&d) || !suppressions.suppressing(&d).is_empty() {
+            continue;
+        }
+        if let Some(family) = scanner.ratchet_family(&d.code) {
+            *counts.entry(family.to_string()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn scan_lines(
+    path: &Path,
+    lines: &[&str],
+    run: &Run,
+    scanner: &Scanner,
+) -> Vec<diagnostic::Diagnostic> {
+    let config: &crate::config::Conf = &run.config;
+
+    let tokens = scanner.tokens_for(path, config);
+    let dnl = config.donotland.enabled
+        && !run.is_upstream()
+        && config.selects(&config.donotland.include, &config.donotland.exclude, path);
+    let todo =
+        config.todo.enabled && config.selects(&config.todo.include, &config.todo.exclude, path);
+
+    // Files in languages the lexer doesn't know are scanned in full
+    let mut lexer = if scanner.needs_lexer() {
+        Lexer::for_path(path)
+    } else {
+        None
+    };
+
+    let mut ret = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let spans = lexer.as_mut().map(|l| l.spans(line));
+        let spans = spans.as_deref();
+
+        // DO-NOT-LAND should always fire and not use HTL semantics. So only generate
+        // those warnings on the current code and skip the upstream
+        if dnl {
+            if let Some(m) =
+                captures_in(&DNL_RE, line, &scanner.dnl_spans, spans).and_then(|c| c.get(0))
+            {
+                // A dated marker, e.g. DONOTLAND(2026-12-31), is allowed until the date passes
+                let expires = parse_todo_annotation(&line[m.end()..], None)
+                    .expires
+                    .and_then(|e| Some((date::parse_ymd(&e)?, e)));
+                let message = match expires {
+                    Some((deadline, _)) if deadline >= run.today => None,
+                    Some((deadline, e)) => Some(format!(
+                        "Found '{}', expired on {} ({} days overdue)",
+                        m.as_str(),
+                        e,
+                        run.today - deadline
+                    )),
+                    None => Some(format!("Found '{}'", m.as_str())),
+                };
+                if let Some(message) = message {
+                    ret.push(diagnostic::Diagnostic {
+                        path: path.to_str().unwrap().to_string(),
+                        range: Some(diagnostic::Range {
+                            start: diagnostic::Position {
+                                line: i as u64,
+                                character: m.start() as u64,
+                                unit: diagnostic::ColumnUnit::Utf8,
+                            },
+                            end: diagnostic::Position {
+                                line: i as u64,
+                                character: m.end() as u64,
+                                unit: diagnostic::ColumnUnit::Utf8,
+                            },
+                        }),
+                        severity: diagnostic::Severity::Error,
+                        code: "do-not-land".to_string(),
+                        message,
+                        replacements: None,
+                    });
+                }
+            }
+        }
+        if todo {
+            if let Some(m) = captures_in(&TODO_RE, line, &scanner.todo_spans, spans) {
                 let token = &m[1];
+                let rest = &line[m.get(1).unwrap().end()..];
+                if let Some((severity, code, message)) = scanner.todo.violation(token, rest) {
+                    ret.push(diagnostic::Diagnostic {
+                        path: path.to_str().unwrap().to_string(),
+                        range: Some(diagnostic::Range {
+                            start: diagnostic::Position {
+                                line: i as u64,
+                                character: m.get(1).unwrap().start() as u64,
+                                unit: diagnostic::ColumnUnit::Utf8,
+                            },
+                            end: diagnostic::Position {
+                                line: i as u64,
+                                // Remove one since we also check for a nonalpha character after the token.
+                                character: m.get(1).unwrap().end() as u64,
+                                unit: diagnostic::ColumnUnit::Utf8,
+                            },
+                        }),
+                        severity,
+                        code: code.to_string(),
+                        message,
+                        replacements: None,
+                    });
+                }
+            }
+        }
+        for token in &tokens {
+            if let Some(m) =
+                captures_in(&token.re, line, &token.spans, spans).and_then(|c| c.get(0))
+            {
                 ret.push(diagnostic::Diagnostic {
                     path: path.to_str().unwrap().to_string(),
                     range: Some(diagnostic::Range {
                         start: diagnostic::Position {
                             line: i as u64,
-                            character: m.get(1).unwrap().start() as u64,
+                            character: m.start() as u64,
+                            unit: diagnostic::ColumnUnit::Utf8,
                         },
                         end: diagnostic::Position {
                             line: i as u64,
-                            // Remove one since we also check for a nonalpha character after the token.
-                            character: m.get(1).unwrap().end() as u64,
+                            character: m.end() as u64,
+                            unit: diagnostic::ColumnUnit::Utf8,
                         },
                     }),
-                    // Lower severity than DNL
-                    severity: diagnostic::Severity::Warning,
-                    code: "todo".to_string(),
-                    message: format!("Found '{}'", token),
+                    severity: token.severity.clone(),
+                    code: token.code.clone(),
+                    message: token.message.replace("{match}", m.as_str()),
                     replacements: None,
                 });
             }
         }
     }
 
-    Ok(ret)
+    ret
+}
+
+/// First match of `re` in `line` that starts in one of the `allowed` kinds of
+/// span. Without lexed `spans` the first match is returned.
+fn captures_in<'h>(
+    re: &Regex,
+    line: &'h str,
+    allowed: &[SpanKind],
+    spans: Option<&[Span]>,
+) -> Option<Captures<'h>> {
+    let mut start = 0;
+    while start <= line.len() {
+        let caps = re.captures_at(line, start)?;
+        let m = caps.get(0).unwrap();
+        if spans.is_none_or(|spans| allowed.contains(&lexer::kind_at(spans, m.start()))) {
+            return Some(caps);
+        }
+        start = if m.end() > m.start() {
+            m.end()
+        } else {
+            m.start() + line[m.start()..].chars().next().map_or(1, char::len_utf8)
+        };
+    }
+    None
+}
+
+/// One TODO found by `toolbox todos`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoItem {
+    pub path: String,
+    /// 1-indexed line number
+    pub line: u64,
+    pub token: String,
+    /// Text following the marker and its annotation
+    pub text: String,
+    pub owner: Option<String>,
+    pub ticket: Option<String>,
+    /// Days since the line was last changed, from git blame; None if uncommitted
+    pub age_days: Option<i64>,
+}
+
+/// Collect every TODO in the tracked files of the repository, regardless of
+/// whether the todo rule would report it, except the config file at
+/// `config_path`. Ages come from git blame, which is only run when `blame` is set.
+pub fn todo_inventory(
+    mut config: Conf,
+    config_path: &str,
+    workspace: Option<&Path>,
+    today: i64,
+    blame: bool,
+) -> anyhow::Result<Vec<TodoItem>> {
+    let workdir = git::repo_workdir(workspace)?;
+
+    // Scan for TODOs alone, keeping the include/exclude and spans settings
+    config.todo.enabled = true;
+    config.todo.ticket_index = None;
+    config.donotland.enabled = false;
+    config.tokens.clear();
+    let run = Run {
+        paths: HashSet::new(),
+        config,
+        config_path: String::new(),
+        cache_dir: String::new(),
+        upstream_mode: false,
+        workspace: workspace.map(Path::to_path_buf),
+        today,
+    };
+    let mut scanner = Scanner::compile(&run)?;
+    scanner.todo.inventory = true;
+
+    let config_file = std::path::absolute(config_path)
+        .map(|path| run::relative_path(&workdir, &path))
+        .unwrap_or_else(|_| PathBuf::from(config_path));
+    let mut files = git::tracked_files(workspace)?;
+    files.retain(|file| Path::new(file) != config_file);
+    files.sort();
+
+    let results: anyhow::Result<Vec<Vec<TodoItem>>> = files
+        .par_iter()
+        .map(|file| {
+            let path = Path::new(file);
+            if !scanner.selects(path, &run.config) {
+                return Ok(vec![]);
+            }
+            let Ok(Contents::Text { text: content, .. }) = encoding::read(&workdir.join(file))
+            else {
+                debug!("skipping binary or undecodable file {}", file);
+                return Ok(vec![]);
+            };
+
+            let lines: Vec<&str> = content.lines().collect();
+            let mut items = Vec::new();
+            for d in scan_lines(path, &lines, &run, &scanner) {
+                let Some(range) = d.range else {
+                    continue;
+                };
+                let line = lines[range.start.line as usize];
+                let token = &line[range.start.character as usize..range.end.character as usize];
+                let rest = &line[range.end.character as usize..];
+                let annotation = parse_todo_annotation(rest, scanner.todo.require_ticket.as_ref());
+                let text = TODO_ANNOTATION_RE
+                    .find(rest)
+                    .map_or(rest, |a| &rest[a.end()..]);
+                items.push(TodoItem {
+                    path: file.clone(),
+                    line: range.start.line + 1,
+                    token: token.to_string(),
+                    text: text
+                        .trim_start_matches(|c: char| c == ':' || c == '-' || c.is_whitespace())
+                        .trim_end()
+                        .to_string(),
+                    owner: annotation.owner,
+                    ticket: annotation.ticket,
+                    age_days: None,
+                });
+            }
+
+            if blame && !items.is_empty() {
+                let times = git::blame_times(file, workspace)
+                    .with_context(|| format!("failed to blame {}", file))?;
+                for item in &mut items {
+                    item.age_days = times
+                        .get(item.line as usize - 1)
+                        .copied()
+                        .flatten()
+                        .map(|seconds| today - seconds.div_euclid(86400));
+                }
+            }
+            Ok(items)
+        })
+        .collect();
+
+    Ok(results?.into_iter().flatten().collect())
 }
@@ -0,0 +1,174 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::config::PortablePathsConf;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::git;
+use crate::run::Run;
+
+use log::{debug, trace};
+
+/// Device names Windows reserves in every directory, whatever the extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Printable characters NTFS does not allow in names
+const INVALID_CHARACTERS: &[char] = &['<', '>', ':', '"', '|', '?', '*', '\\'];
+
+/// Backslashes are kept: on Windows they would split the name in two.
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy().trim_start_matches("./").to_string()
+}
+
+/// `path` and each of its parent directories, shortest first.
+fn prefixes(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .map(|(i, _)| &path[..i])
+        .chain(std::iter::once(path))
+}
+
+fn is_reserved(component: &str) -> bool {
+    let stem = component.split('.').next().unwrap_or(component).trim_end();
+    RESERVED_NAMES
+        .iter()
+        .any(|name| stem.eq_ignore_ascii_case(name))
+}
+
+pub fn portable_paths(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.portablepaths;
+
+    if !config.enabled {
+        trace!("'portablepaths' is disabled");
+        return Ok(vec![]);
+    }
+
+    debug!("checking {} paths for portability", run.paths.len());
+
+    let mut paths: Vec<String> = run
+        .paths
+        .iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| normalize(path))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Every spelling of each tracked file and directory, keyed by its lowercase form
+    let mut spellings: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let tracked = git::tracked_files(run.workspace())?;
+    for path in tracked.iter().chain(paths.iter()) {
+        for prefix in prefixes(path) {
+            spellings
+                .entry(prefix.to_lowercase())
+                .or_default()
+                .insert(prefix.to_string());
+        }
+    }
+
+    Ok(paths
+        .iter()
+        .flat_map(|path| portable_paths_impl(path, config, &spellings))
+        .collect())
+}
+
+fn portable_paths_impl(
+    path: &str,
+    config: &PortablePathsConf,
+    spellings: &HashMap<String, BTreeSet<String>>,
+) -> Vec<Diagnostic> {
+    let mut ret = Vec::new();
+    let mut push = |code: &str, message: String| {
+        ret.push(Diagnostic {
+            path: path.to_string(),
+            range: None,
+            severity: Severity::Error,
+            code: code.to_string(),
+            message,
+            replacements: None,
+        });
+    };
+
+    // Report the outermost collision only; everything below it collides too
+    let collision = prefixes(path).find_map(|prefix| {
+        spellings
+            .get(&prefix.to_lowercase())?
+            .iter()
+            .find(|other| *other != prefix)
+            .map(|other| (prefix, other))
+    });
+    if let Some((prefix, other)) = collision {
+        push(
+            "path-case-collision",
+            format!(
+                "'{}' and '{}' differ only in case and collide on case-insensitive file systems",
+                prefix, other
+            ),
+        );
+    }
+
+    for component in path.split('/') {
+        if is_reserved(component) {
+            push(
+                "path-reserved-name",
+                format!("'{}' is a reserved device name on Windows", component),
+            );
+        }
+        if component.ends_with('.') || component.ends_with(' ') {
+            push(
+                "path-trailing-dot-or-space",
+                format!(
+                    "'{}' ends with a dot or space, which Windows strips from names",
+                    component
+                ),
+            );
+        }
+        if let Some(c) = component
+            .chars()
+            .find(|c| INVALID_CHARACTERS.contains(c) || c.is_ascii_control())
+        {
+            push(
+                "path-invalid-character",
+                format!(
+                    "'{}' contains {:?}, which is not allowed in names on Windows",
+                    component, c
+                ),
+            );
+        }
+    }
+
+    let length = path.chars().count();
+    if length > config.max_length {
+        push(
+            "path-too-long",
+            format!(
+                "Path is {} characters long, over the limit of {}",
+                length, config.max_length
+            ),
+        );
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_names_ignore_case_and_extension() {
+        assert!(is_reserved("CON"));
+        assert!(is_reserved("aux.txt"));
+        assert!(is_reserved("Com1.tar.gz"));
+        assert!(!is_reserved("console.rs"));
+        assert!(!is_reserved("COM10"));
+        assert_eq!(
+            prefixes("a/b/c").collect::<Vec<_>>(),
+            vec!["a", "a/b", "a/b/c"]
+        );
+    }
+}
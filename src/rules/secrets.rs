@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::SecretsConf;
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Severity};
+use crate::encoding::{self, Contents};
+use crate::git;
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Credential formats recognised by their shape alone: code, description and
+/// the pattern, whose first capture group (or whole match) is the secret.
+static DETECTORS: LazyLock<Vec<(&str, &str, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            "secret-private-key",
+            "private key",
+            r"-----BEGIN (?:(?:RSA|DSA|EC|OPENSSH|PGP|ENCRYPTED) )?PRIVATE KEY(?: BLOCK)?-----",
+        ),
+        (
+            "secret-aws-access-key",
+            "AWS access key ID",
+            r"\b((?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16})\b",
+        ),
+        (
+            "secret-gcp-api-key",
+            "Google Cloud API key",
+            r"\b(AIza[0-9A-Za-z_\-]{35})",
+        ),
+        (
+            "secret-github-token",
+            "GitHub token",
+            r"\b(gh[pousr]_[A-Za-z0-9]{36}|github_pat_[A-Za-z0-9_]{82})\b",
+        ),
+        (
+            "secret-slack-token",
+            "Slack token",
+            r"\b(xox[abposr]-[A-Za-z0-9-]{10,})",
+        ),
+        (
+            "secret-jwt",
+            "JSON Web Token",
+            r"\b(eyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,})",
+        ),
+    ]
+    .into_iter()
+    .map(|(code, description, pattern)| (code, description, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+/// A value assigned to a name that suggests a credential, e.g. `DB_PASSWORD = "..."`
+static ASSIGNMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)([\w.-]*(?:password|passwd|secret|token|api[_-]?key|access[_-]?key|private[_-]?key|credential)[\w.-]*)["']?\s*(?::=|=>|=|:)\s*["'`]?([^\s"'`,;]{8,})"#,
+    )
+    .unwrap()
+});
+
+/// Shannon entropy of `value`, in bits per character.
+fn entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Enough of `value` to find it again, and not enough to use it.
+fn redact(value: &str) -> String {
+    let shown: String = value.chars().take(4).collect();
+    if value.chars().count() >= 12 {
+        format!("{}****", shown)
+    } else {
+        "****".to_string()
+    }
+}
+
+pub fn secrets(run: &Run, upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.secrets;
+
+    if !config.enabled {
+        trace!("'secrets' is disabled");
+        return Ok(vec![]);
+    }
+
+    if config.changed_only && run.is_upstream() {
+        trace!("'secrets' only checks changed lines; skipped on upstream baseline run");
+        return Ok(vec![]);
+    }
+
+    let allowlist = config
+        .allowlist
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).with_context(|| format!("invalid secrets.allowlist {:?}", pattern))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    debug!("scanning {} files for secrets", run.paths.len());
+
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| secrets_impl(path, config, &allowlist))
+        .collect();
+
+    let ret: Vec<Diagnostic> = results?.into_iter().flatten().collect();
+
+    if !config.changed_only || ret.is_empty() {
+        return Ok(ret);
+    }
+
+    // Keep only secrets on lines added or modified since upstream
+    let modified = git::modified_since(upstream, run.workspace())?;
+    let mut changed_lines: HashMap<PathBuf, HashSet<u64>> = HashMap::new();
+    for h in &modified.hunks {
+        changed_lines
+            .entry(h.path.clone())
+            .or_default()
+            .extend(h.begin..h.end);
+    }
+
+    Ok(ret
+        .into_iter()
+        .filter(|d| {
+            let Some(range) = &d.range else {
+                return false;
+            };
+            changed_lines
+                .get(Path::new(&d.path))
+                .is_some_and(|lines| lines.contains(&(range.start.line + 1)))
+        })
+        .collect())
+}
+
+fn secrets_impl(
+    path: &Path,
+    config: &SecretsConf,
+    allowlist: &[Regex],
+) -> anyhow::Result<Vec<Diagnostic>> {
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let text = match contents {
+        Contents::Text { text, .. } => text,
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "secrets", &reason)]);
+        }
+    };
+
+    trace!("scanning contents of {}", path.display());
+
+    let mut ret = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        // Byte ranges already reported on this line, so an assignment of a
+        // recognised token isn't reported twice
+        let mut found: Vec<(usize, usize)> = Vec::new();
+        let mut push = |code: &str, message: String, start: usize, end: usize| {
+            let position = |character: usize| Position {
+                line: i as u64,
+                character: character as u64,
+                unit: ColumnUnit::Utf8,
+            };
+            ret.push(Diagnostic {
+                path: path.to_string_lossy().to_string(),
+                range: Some(Range {
+                    start: position(start),
+                    end: position(end),
+                }),
+                severity: Severity::Error,
+                code: code.to_string(),
+                message,
+                replacements: None,
+            });
+        };
+        let allowed = |value: &str| allowlist.iter().any(|re| re.is_match(value));
+
+        for (code, description, re) in DETECTORS.iter() {
+            for caps in re.captures_iter(line) {
+                let m = caps.get(1).or_else(|| caps.get(0)).unwrap();
+                if allowed(m.as_str()) {
+                    continue;
+                }
+                found.push((m.start(), m.end()));
+                let message = if *code == "secret-private-key" {
+                    format!("Found {}", description)
+                } else {
+                    format!("Found {} '{}'", description, redact(m.as_str()))
+                };
+                push(code, message, m.start(), m.end());
+            }
+        }
+
+        for caps in ASSIGNMENT_RE.captures_iter(line) {
+            let (name, value) = (caps.get(1).unwrap(), caps.get(2).unwrap());
+            if found
+                .iter()
+                .any(|&(start, end)| start < value.end() && value.start() < end)
+            {
+                continue;
+            }
+            if entropy(value.as_str()) < config.min_entropy || allowed(value.as_str()) {
+                continue;
+            }
+            push(
+                "secret-assignment",
+                format!(
+                    "Found high-entropy value '{}' assigned to '{}'",
+                    redact(value.as_str()),
+                    name.as_str()
+                ),
+                value.start(),
+                value.end(),
+            );
+        }
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entropy_separates_placeholders_from_keys() {
+        assert!(entropy("changeme") < 3.5);
+        assert!(entropy("xxxxxxxxxxxx") < 1.0);
+        assert!(entropy("q8ZtR2vLk9Wm4PxN") > 3.5);
+        assert_eq!(redact("q8ZtR2vLk9Wm4PxN"), "q8Zt****");
+        assert_eq!(redact("hunter22"), "****");
+    }
+}
@@ -0,0 +1,290 @@
+use std::path::Path;
+
+use crate::config::UnicodeCategory;
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
+use crate::encoding::{self, Contents};
+use crate::lexer::{self, Lexer, SpanKind};
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static IDENTIFIER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\p{L}_][\p{L}\p{N}_]*").unwrap());
+
+/// Cyrillic and Greek letters that render like a Latin letter, and that letter.
+const CONFUSABLES: &[(char, char)] = &[
+    ('\u{0430}', 'a'),
+    ('\u{0435}', 'e'),
+    ('\u{043E}', 'o'),
+    ('\u{0440}', 'p'),
+    ('\u{0441}', 'c'),
+    ('\u{0443}', 'y'),
+    ('\u{0445}', 'x'),
+    ('\u{0456}', 'i'),
+    ('\u{0458}', 'j'),
+    ('\u{0455}', 's'),
+    ('\u{0501}', 'd'),
+    ('\u{04BB}', 'h'),
+    ('\u{051B}', 'q'),
+    ('\u{051D}', 'w'),
+    ('\u{0410}', 'A'),
+    ('\u{0412}', 'B'),
+    ('\u{0415}', 'E'),
+    ('\u{041A}', 'K'),
+    ('\u{041C}', 'M'),
+    ('\u{041D}', 'H'),
+    ('\u{041E}', 'O'),
+    ('\u{0420}', 'P'),
+    ('\u{0421}', 'C'),
+    ('\u{0422}', 'T'),
+    ('\u{0425}', 'X'),
+    ('\u{0406}', 'I'),
+    ('\u{0408}', 'J'),
+    ('\u{0405}', 'S'),
+    ('\u{03BF}', 'o'),
+    ('\u{03BD}', 'v'),
+    ('\u{03C1}', 'p'),
+    ('\u{0391}', 'A'),
+    ('\u{0392}', 'B'),
+    ('\u{0395}', 'E'),
+    ('\u{0396}', 'Z'),
+    ('\u{0397}', 'H'),
+    ('\u{0399}', 'I'),
+    ('\u{039A}', 'K'),
+    ('\u{039C}', 'M'),
+    ('\u{039D}', 'N'),
+    ('\u{039F}', 'O'),
+    ('\u{03A1}', 'P'),
+    ('\u{03A4}', 'T'),
+    ('\u{03A5}', 'Y'),
+    ('\u{03A7}', 'X'),
+];
+
+fn confusable(c: char) -> Option<char> {
+    CONFUSABLES
+        .iter()
+        .find(|(lookalike, _)| *lookalike == c)
+        .map(|&(_, latin)| latin)
+}
+
+/// What to report for a single character, if anything: its category,
+/// severity, description and the text to replace it with.
+fn classify(c: char) -> Option<(UnicodeCategory, Severity, &'static str, String)> {
+    match c {
+        '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => Some((
+            UnicodeCategory::Bidi,
+            Severity::Error,
+            "bidirectional control character",
+            String::new(),
+        )),
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => Some((
+            UnicodeCategory::ZeroWidth,
+            Severity::Warning,
+            "zero-width character",
+            String::new(),
+        )),
+        '\u{00A0}' | '\u{2007}' | '\u{202F}' => Some((
+            UnicodeCategory::Nbsp,
+            Severity::Warning,
+            "non-breaking space",
+            " ".to_string(),
+        )),
+        '\u{2013}' | '\u{2014}' => Some((
+            UnicodeCategory::Dashes,
+            Severity::Warning,
+            "en or em dash",
+            "-".to_string(),
+        )),
+        '\u{FF01}'..='\u{FF5E}' => Some((
+            UnicodeCategory::Fullwidth,
+            Severity::Warning,
+            "fullwidth character",
+            char::from_u32(c as u32 - 0xFEE0).unwrap().to_string(),
+        )),
+        '\u{3000}' => Some((
+            UnicodeCategory::Fullwidth,
+            Severity::Warning,
+            "fullwidth space",
+            " ".to_string(),
+        )),
+        _ => None,
+    }
+}
+
+fn code_for(category: UnicodeCategory) -> &'static str {
+    match category {
+        UnicodeCategory::Bidi => "unicode-bidi",
+        UnicodeCategory::ZeroWidth => "unicode-zero-width",
+        UnicodeCategory::Nbsp => "unicode-nbsp",
+        UnicodeCategory::Dashes => "unicode-dash",
+        UnicodeCategory::Fullwidth => "unicode-fullwidth",
+        UnicodeCategory::Confusables => "unicode-confusable",
+    }
+}
+
+pub fn unicode_hygiene(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.unicodehygiene;
+
+    if !config.enabled {
+        trace!("'unicodehygiene' is disabled");
+        return Ok(vec![]);
+    }
+
+    debug!("scanning {} files for unicode hygiene", run.paths.len());
+
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| unicode_hygiene_impl(path, &config.categories))
+        .collect();
+
+    match results {
+        Ok(v) => Ok(v.into_iter().flatten().collect()),
+        Err(e) => Err(e),
+    }
+}
+
+fn unicode_hygiene_impl(
+    path: &Path,
+    categories: &[UnicodeCategory],
+) -> anyhow::Result<Vec<Diagnostic>> {
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let (text, encoding) = match contents {
+        Contents::Text { text, encoding } => (text, encoding),
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "unicode_hygiene", &reason)]);
+        }
+    };
+
+    trace!("scanning contents of {}", path.display());
+
+    // Dashes and fullwidth forms are only a problem in code, so they are only
+    // checked in files whose language the lexer knows
+    let mut lexer = Lexer::for_path(path);
+    let mut ret = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let spans = lexer.as_mut().map(|l| l.spans(line));
+        let in_code = |offset: usize| {
+            spans
+                .as_deref()
+                .map(|spans| lexer::kind_at(spans, offset) == SpanKind::Code)
+        };
+
+        let mut push = |category: UnicodeCategory,
+                        severity: Severity,
+                        message: String,
+                        start: usize,
+                        end: usize,
+                        replacements: Vec<(usize, usize, String)>| {
+            let range = |start: usize, end: usize| Range {
+                start: Position {
+                    line: i as u64,
+                    character: start as u64,
+                    unit: ColumnUnit::Utf8,
+                },
+                end: Position {
+                    line: i as u64,
+                    character: end as u64,
+                    unit: ColumnUnit::Utf8,
+                },
+            };
+            ret.push(Diagnostic {
+                path: path.to_string_lossy().to_string(),
+                range: Some(range(start, end)),
+                severity,
+                code: code_for(category).to_string(),
+                message,
+                // Fixes computed on transcoded text would not line up with the file on disk
+                replacements: encoding.is_utf8().then(|| {
+                    replacements
+                        .into_iter()
+                        .map(|(start, end, inserted_content)| Replacement {
+                            path: None,
+                            deleted_region: range(start, end),
+                            inserted_content,
+                        })
+                        .collect()
+                }),
+            });
+        };
+
+        for (offset, c) in line.char_indices() {
+            let Some((category, severity, description, fix)) = classify(c) else {
+                continue;
+            };
+            if !categories.contains(&category) {
+                continue;
+            }
+            let code_only = matches!(
+                category,
+                UnicodeCategory::Dashes | UnicodeCategory::Fullwidth
+            );
+            if code_only && in_code(offset) != Some(true) {
+                continue;
+            }
+            let end = offset + c.len_utf8();
+            push(
+                category,
+                severity,
+                format!("Found {} U+{:04X}", description, c as u32),
+                offset,
+                end,
+                vec![(offset, end, fix)],
+            );
+        }
+
+        if categories.contains(&UnicodeCategory::Confusables) {
+            for m in IDENTIFIER_RE.find_iter(line) {
+                // Files in unknown languages are checked throughout
+                if in_code(m.start()) == Some(false) {
+                    continue;
+                }
+                let ident = m.as_str();
+                if !ident.chars().any(|c| c.is_ascii_alphabetic()) {
+                    continue;
+                }
+                let fixes: Vec<(usize, usize, String)> = ident
+                    .char_indices()
+                    .filter_map(|(offset, c)| {
+                        let latin = confusable(c)?;
+                        let start = m.start() + offset;
+                        Some((start, start + c.len_utf8(), latin.to_string()))
+                    })
+                    .collect();
+                if fixes.is_empty() {
+                    continue;
+                }
+                let fixed: String = ident.chars().map(|c| confusable(c).unwrap_or(c)).collect();
+                push(
+                    UnicodeCategory::Confusables,
+                    Severity::Warning,
+                    format!(
+                        "Identifier '{}' mixes Latin letters with lookalike characters; did you mean '{}'?",
+                        ident, fixed
+                    ),
+                    m.start(),
+                    m.end(),
+                    fixes,
+                );
+            }
+        }
+    }
+
+    Ok(ret)
+}
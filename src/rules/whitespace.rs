@@ -0,0 +1,247 @@
+use std::path::Path;
+
+use crate::config::WhitespaceConf;
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
+use crate::editorconfig::{self, IndentStyle};
+use crate::encoding::{self, Contents};
+use crate::run::Run;
+
+use anyhow::Context;
+use log::{debug, trace};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+pub fn whitespace(run: &Run, _upstream: &str) -> anyhow::Result<Vec<Diagnostic>> {
+    let config = &run.config.whitespace;
+
+    if !config.enabled {
+        trace!("'whitespace' is disabled");
+        return Ok(vec![]);
+    }
+
+    debug!("scanning {} files for whitespace", run.paths.len());
+
+    let results: Result<Vec<_>, _> = run
+        .paths
+        .par_iter()
+        .filter(|path| run.config.selects(&config.include, &config.exclude, path))
+        .map(|path| whitespace_impl(path, config))
+        .collect();
+
+    match results {
+        Ok(v) => Ok(v.into_iter().flatten().collect()),
+        Err(e) => Err(e),
+    }
+}
+
+fn position(line: usize, character: usize) -> Position {
+    Position {
+        line: line as u64,
+        character: character as u64,
+        unit: ColumnUnit::Utf8,
+    }
+}
+
+fn replacement(start: Position, end: Position, inserted_content: &str) -> Replacement {
+    Replacement {
+        path: None,
+        deleted_region: Range { start, end },
+        inserted_content: inserted_content.to_string(),
+    }
+}
+
+/// Leading whitespace rewritten in `style`, with `size` columns per level.
+/// Spaces that don't make up a whole level are kept for alignment.
+fn reindent(leading: &str, style: IndentStyle, size: usize) -> String {
+    let width: usize = leading
+        .chars()
+        .map(|c| if c == '\t' { size } else { 1 })
+        .sum();
+    match style {
+        IndentStyle::Space => " ".repeat(width),
+        IndentStyle::Tab => format!("{}{}", "\t".repeat(width / size), " ".repeat(width % size)),
+    }
+}
+
+fn whitespace_impl(path: &Path, config: &WhitespaceConf) -> anyhow::Result<Vec<Diagnostic>> {
+    // Deleted files are passed along with the rest of a change
+    if !path.exists() {
+        trace!("ignoring deleted file {}", path.display());
+        return Ok(vec![]);
+    }
+
+    let contents = encoding::read(path).with_context(|| format!("failed to open: {:#?}", path))?;
+    let (text, encoding) = match contents {
+        Contents::Text { text, encoding } => (text, encoding),
+        Contents::Binary => {
+            debug!("ignoring binary file {}", path.display());
+            return Ok(vec![]);
+        }
+        Contents::Undecodable(reason) => {
+            return Ok(vec![encoding::skipped(path, "whitespace", &reason)]);
+        }
+    };
+
+    if text.is_empty() {
+        return Ok(vec![]);
+    }
+
+    trace!("scanning contents of {}", path.display());
+
+    let indentation = if config.indent_style.is_some() && config.indent_size.is_some() {
+        editorconfig::Indentation::default()
+    } else {
+        editorconfig::indentation(path)
+    };
+    let indent_style = config.indent_style.or(indentation.style);
+    let indent_size = config.indent_size.or(indentation.size).unwrap_or(4).max(1);
+
+    let path_str = path.to_string_lossy().to_string();
+    let mut ret = Vec::new();
+    let mut push = |code: &str, range: Range, message: String, replacements: Vec<Replacement>| {
+        ret.push(Diagnostic {
+            path: path_str.clone(),
+            range: Some(range),
+            severity: Severity::Warning,
+            code: code.to_string(),
+            message,
+            // Fixes computed on transcoded text would not line up with the file on disk
+            replacements: encoding.is_utf8().then_some(replacements),
+        });
+    };
+
+    // Every line with its terminator: "\r\n", "\n" or "" for the last line
+    let lines: Vec<(&str, &str)> = text
+        .split_inclusive('\n')
+        .map(|l| match l.strip_suffix("\r\n") {
+            Some(body) => (body, "\r\n"),
+            None => match l.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None => (l, ""),
+            },
+        })
+        .collect();
+
+    let crlf = lines.iter().filter(|(_, end)| *end == "\r\n").count();
+    let lf = lines.iter().filter(|(_, end)| *end == "\n").count();
+    let newline = if crlf > lf { "\r\n" } else { "\n" };
+
+    for (i, (body, _)) in lines.iter().enumerate() {
+        if config.trailing_whitespace {
+            let trimmed = body.trim_end_matches([' ', '\t']);
+            if trimmed.len() < body.len() {
+                let range = Range {
+                    start: position(i, trimmed.len()),
+                    end: position(i, body.len()),
+                };
+                push(
+                    "trailing-whitespace",
+                    range.clone(),
+                    "Trailing whitespace".to_string(),
+                    vec![replacement(range.start, range.end, "")],
+                );
+            }
+        }
+
+        if let Some(style) = indent_style {
+            let leading_len = body.len() - body.trim_start_matches([' ', '\t']).len();
+            let leading = &body[..leading_len];
+            // Whitespace-only lines are left to the trailing whitespace check
+            if leading_len < body.len() {
+                let wrong = match style {
+                    IndentStyle::Tab => leading.contains(&" ".repeat(indent_size)),
+                    IndentStyle::Space => leading.contains('\t'),
+                };
+                if wrong {
+                    let range = Range {
+                        start: position(i, 0),
+                        end: position(i, leading_len),
+                    };
+                    let expected = match style {
+                        IndentStyle::Tab => "tabs",
+                        IndentStyle::Space => "spaces",
+                    };
+                    push(
+                        "inconsistent-indentation",
+                        range.clone(),
+                        format!("Indentation should use {}", expected),
+                        vec![replacement(
+                            range.start,
+                            range.end,
+                            &reindent(leading, style, indent_size),
+                        )],
+                    );
+                }
+            }
+        }
+    }
+
+    if config.mixed_line_endings && crlf > 0 && lf > 0 {
+        let minority = if newline == "\r\n" { "\n" } else { "\r\n" };
+        let fixes: Vec<Replacement> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, end))| *end == minority)
+            .map(|(i, (body, _))| {
+                if minority == "\r\n" {
+                    // Delete the "\r"
+                    replacement(position(i, body.len()), position(i, body.len() + 1), "")
+                } else {
+                    replacement(position(i, body.len()), position(i, body.len()), "\r")
+                }
+            })
+            .collect();
+        let first = fixes[0].deleted_region.clone();
+        push(
+            "mixed-line-endings",
+            first,
+            format!(
+                "File mixes CRLF and LF line endings; {} line(s) end with {} instead of {}",
+                fixes.len(),
+                if minority == "\r\n" { "CRLF" } else { "LF" },
+                if newline == "\r\n" { "CRLF" } else { "LF" },
+            ),
+            fixes,
+        );
+    }
+
+    let last = lines.len() - 1;
+    let (last_body, last_end) = lines[last];
+    if config.final_newline && last_end.is_empty() {
+        let at = position(last, last_body.len());
+        push(
+            "missing-final-newline",
+            Range {
+                start: at.clone(),
+                end: at.clone(),
+            },
+            "File does not end with a newline".to_string(),
+            vec![replacement(at.clone(), at, newline)],
+        );
+    }
+
+    if config.trailing_blank_lines && !last_end.is_empty() {
+        // Lines after the last one with content, which keeps its own newline
+        let content_lines = lines
+            .iter()
+            .rposition(|(body, _)| !body.trim().is_empty())
+            .map_or(0, |i| i + 1);
+        if lines.len() > content_lines {
+            let start = position(content_lines, 0);
+            let end = position(lines.len(), 0);
+            push(
+                "trailing-blank-lines",
+                Range {
+                    start: start.clone(),
+                    end: end.clone(),
+                },
+                format!(
+                    "File ends with {} blank line(s)",
+                    lines.len() - content_lines
+                ),
+                vec![replacement(start, end, "")],
+            );
+        }
+    }
+
+    Ok(ret)
+}
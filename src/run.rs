@@ -1,6 +1,6 @@
 use crate::config::Conf;
 use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use clap::builder::PossibleValue;
 use clap::{Parser, Subcommand, ValueEnum};
@@ -54,6 +54,10 @@ pub struct Cli {
     #[clap(long)]
     /// path to the git workspace to operate on; defaults to the current working directory
     pub workspace: Option<PathBuf>,
+
+    #[clap(long, env = "TOOLBOX_TODAY")]
+    /// override the current date (YYYY-MM-DD) used to evaluate dated TODOs
+    pub today: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -61,6 +65,86 @@ pub enum Subcommands {
     // print default config for toolbox
     /// Generate default configuration content for toolbox
     Genconfig,
+
+    /// Inspect the never-edit rule configuration
+    NeverEdit {
+        #[command(subcommand)]
+        command: NeverEditCommand,
+    },
+
+    /// Report every TODO in the tracked files of the repository
+    Todos {
+        #[clap(long, default_value = "csv")]
+        format: TodosFormat,
+
+        #[clap(long)]
+        /// group the TODOs by file, owner or ticket
+        group_by: Option<TodosGroupBy>,
+
+        #[clap(long, value_delimiter = ',')]
+        /// comma-separated columns to include, in order; all of them by default
+        columns: Vec<TodoColumn>,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum TodosFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum TodosGroupBy {
+    File,
+    Owner,
+    Ticket,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum TodoColumn {
+    Path,
+    Line,
+    Token,
+    Owner,
+    Ticket,
+    #[value(name = "age_days")]
+    AgeDays,
+    Text,
+}
+
+impl TodoColumn {
+    pub const ALL: [TodoColumn; 7] = [
+        TodoColumn::Path,
+        TodoColumn::Line,
+        TodoColumn::Token,
+        TodoColumn::Owner,
+        TodoColumn::Ticket,
+        TodoColumn::AgeDays,
+        TodoColumn::Text,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TodoColumn::Path => "path",
+            TodoColumn::Line => "line",
+            TodoColumn::Token => "token",
+            TodoColumn::Owner => "owner",
+            TodoColumn::Ticket => "ticket",
+            TodoColumn::AgeDays => "age_days",
+            TodoColumn::Text => "text",
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NeverEditCommand {
+    /// List tracked files protected by never-edit and the glob protecting each
+    Ls {
+        #[clap(long)]
+        /// only list protected files at or below this path
+        path: Option<String>,
+    },
 }
 
 pub struct Run {
@@ -70,6 +154,8 @@ pub struct Run {
     pub cache_dir: String,
     pub upstream_mode: bool,
     pub workspace: Option<PathBuf>,
+    /// Current date as days since the Unix epoch, see `crate::date`
+    pub today: i64,
 }
 
 impl Run {
@@ -80,4 +166,23 @@ impl Run {
     pub fn workspace(&self) -> Option<&Path> {
         self.workspace.as_deref()
     }
+
+    /// Absolute path of the workspace, or of the current directory when there is none.
+    pub fn root(&self) -> PathBuf {
+        workspace_root(self.workspace())
+    }
+}
+
+pub fn workspace_root(workspace: Option<&Path>) -> PathBuf {
+    let root = workspace.unwrap_or(Path::new("."));
+    std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf())
+}
+
+/// `path` relative to `root` when it lies inside it, without `./` components.
+pub fn relative_path(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
 }
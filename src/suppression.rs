@@ -0,0 +1,384 @@
+//! Inline suppression comments shared by every rule.
+//!
+//! Supported directives, each optionally followed by a parenthesized,
+//! comma-separated list of codes (`todo` or `trunk-toolbox/todo`). Without a
+//! code list a directive suppresses every code. Directives are only honored
+//! inside comments, and not when quoted in backticks as they are here.
+//!
+//! - `toolbox-ignore(code)` suppresses diagnostics on the same line
+//! - `toolbox-ignore-next-line(code)` suppresses diagnostics on the following line
+//! - `toolbox-ignore-begin(code)` ... `toolbox-ignore-end(code)` suppresses the lines in between
+//! - `toolbox-ignore-all(code)` suppresses diagnostics anywhere in the file, and
+//!   always needs a code list
+use crate::diagnostic::{ColumnUnit, Diagnostic, Position, Range, Replacement, Severity};
+use crate::encoding::{self, Contents};
+use crate::lexer::{self, Lexer, SpanKind};
+use crate::run;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static DIRECTIVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\btoolbox-ignore(?:-(next-line|begin|end|all))?(?:\(([^)]*)\))?").unwrap()
+});
+
+/// Comment markers looked for before a directive in files the lexer doesn't know
+const COMMENT_MARKERS: &[&str] = &["//", "/*", "#", "--", ";", "<!--"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    Line,
+    NextLine,
+    Begin,
+    End,
+    All,
+}
+
+#[derive(Debug, Clone)]
+pub struct Directive {
+    pub kind: DirectiveKind,
+    /// Codes this directive applies to; empty means every code
+    pub codes: Vec<String>,
+    /// 0-indexed line the directive appears on
+    pub line: u64,
+    /// Byte offsets of the directive text within its line
+    pub start: usize,
+    pub end: usize,
+    /// Inclusive 0-indexed line range covered; `None` covers the whole file
+    pub covers: Option<(u64, u64)>,
+    /// Index of the matching end for a begin directive, and vice versa
+    pub partner: Option<usize>,
+}
+
+impl Directive {
+    fn applies_to(&self, code: &str) -> bool {
+        self.codes.is_empty() || self.codes.iter().any(|c| c == code)
+    }
+
+    fn covers_line(&self, line: u64) -> bool {
+        match self.covers {
+            Some((first, last)) => first <= line && line <= last,
+            None => true,
+        }
+    }
+}
+
+/// All suppression directives found in one file.
+#[derive(Debug, Default)]
+pub struct FileSuppressions {
+    pub directives: Vec<Directive>,
+}
+
+impl FileSuppressions {
+    /// Directives in the comments of `text`, the contents of `path`.
+    pub fn parse(path: &Path, text: &str) -> FileSuppressions {
+        let mut directives: Vec<Directive> = Vec::new();
+        // Indices into `directives` of begin directives still waiting for their end
+        let mut open: Vec<usize> = Vec::new();
+        let mut last_line = 0;
+        // Markdown is prose; only its HTML comments can hold directives
+        let markdown = path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+        });
+        let mut lexer = if markdown {
+            Some(Lexer::new(&lexer::MARKUP))
+        } else {
+            Lexer::for_path(path)
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            let i = i as u64;
+            last_line = i;
+            let spans = lexer.as_mut().map(|l| l.spans(line));
+            for caps in DIRECTIVE_RE.captures_iter(line) {
+                let m = caps.get(0).unwrap();
+                // Part of a longer word, e.g. `toolbox-ignored`
+                if caps.get(2).is_none()
+                    && line[m.end()..]
+                        .starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '-')
+                {
+                    continue;
+                }
+                if line[..m.start()].ends_with('`') {
+                    continue;
+                }
+                let in_comment = match &spans {
+                    Some(spans) => lexer::kind_at(spans, m.start()) == SpanKind::Comment,
+                    None => COMMENT_MARKERS
+                        .iter()
+                        .any(|marker| line[..m.start()].contains(marker)),
+                };
+                if !in_comment {
+                    continue;
+                }
+                let kind = match caps.get(1).map(|k| k.as_str()) {
+                    None => DirectiveKind::Line,
+                    Some("next-line") => DirectiveKind::NextLine,
+                    Some("begin") => DirectiveKind::Begin,
+                    Some("end") => DirectiveKind::End,
+                    _ => DirectiveKind::All,
+                };
+                let codes: Vec<String> = caps
+                    .get(2)
+                    .map(|c| {
+                        c.as_str()
+                            .split(',')
+                            .map(|code| code.trim().trim_start_matches("trunk-toolbox/"))
+                            .filter(|code| !code.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                // Hiding every issue in a file takes naming what is being hidden
+                if kind == DirectiveKind::All && codes.is_empty() {
+                    continue;
+                }
+
+                let mut partner = None;
+                let covers = match kind {
+                    DirectiveKind::Line => Some((i, i)),
+                    DirectiveKind::NextLine => Some((i + 1, i + 1)),
+                    DirectiveKind::Begin => {
+                        open.push(directives.len());
+                        Some((i, i))
+                    }
+                    DirectiveKind::End => {
+                        // Close the innermost begin with the same codes, or failing that the innermost begin
+                        let pos = open
+                            .iter()
+                            .rposition(|&b| directives[b].codes == codes)
+                            .or_else(|| open.len().checked_sub(1));
+                        if let Some(pos) = pos {
+                            let begin = open.remove(pos);
+                            directives[begin].covers = Some((directives[begin].line, i));
+                            directives[begin].partner = Some(directives.len());
+                            partner = Some(begin);
+                        }
+                        Some((i, i))
+                    }
+                    DirectiveKind::All => None,
+                };
+
+                directives.push(Directive {
+                    kind,
+                    codes,
+                    line: i,
+                    start: m.start(),
+                    end: m.end(),
+                    covers,
+                    partner,
+                });
+            }
+        }
+
+        // An unterminated begin runs to the end of the file
+        for begin in open {
+            directives[begin].covers = Some((directives[begin].line, last_line));
+        }
+
+        FileSuppressions { directives }
+    }
+
+    /// Directives in the file at `path`, decoded the same way the rules read it.
+    /// Binary and undecodable files have none.
+    pub fn from_file(path: &Path) -> std::io::Result<FileSuppressions> {
+        Ok(match encoding::read(path)? {
+            Contents::Text { text, .. } => FileSuppressions::parse(path, &text),
+            Contents::Binary | Contents::Undecodable(_) => FileSuppressions::default(),
+        })
+    }
+
+    /// Whether `diagnostic` points entirely inside the text of a directive, e.g.
+    /// the `todo` in `toolbox-ignore(todo)` matched by the todo rule.
+    pub fn within_directive(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(range) = &diagnostic.range else {
+            return false;
+        };
+        range.start.line == range.end.line
+            && self.directives.iter().any(|d| {
+                d.line == range.start.line
+                    && d.start as u64 <= range.start.character
+                    && range.end.character <= d.end as u64
+            })
+    }
+
+    /// Indices of the directives that suppress `diagnostic`. Diagnostics
+    /// without a range can only be suppressed by `toolbox-ignore-all`.
+    pub fn suppressing(&self, diagnostic: &Diagnostic) -> Vec<usize> {
+        self.directives
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| {
+                d.kind != DirectiveKind::End
+                    && d.applies_to(&diagnostic.code)
+                    && match &diagnostic.range {
+                        Some(range) => d.covers_line(range.start.line),
+                        None => d.kind == DirectiveKind::All,
+                    }
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Applies suppressions across a run, remembering which directives were used so
+/// the unused ones can be reported once every rule has finished.
+pub struct Suppressor {
+    /// Absolute directory that relative paths are resolved against
+    root: PathBuf,
+    /// Keyed by path relative to `root`, so `./a.rs` and `a.rs` share an entry
+    files: HashMap<PathBuf, (FileSuppressions, Vec<bool>)>,
+}
+
+impl Suppressor {
+    /// A suppressor for paths relative to `workspace`, or to the current
+    /// directory when there is none.
+    pub fn new(workspace: Option<&Path>) -> Suppressor {
+        Suppressor {
+            root: run::workspace_root(workspace),
+            files: HashMap::new(),
+        }
+    }
+
+    fn key(&self, path: &Path) -> PathBuf {
+        run::relative_path(&self.root, path)
+    }
+
+    fn file(&mut self, path: &Path) -> &mut (FileSuppressions, Vec<bool>) {
+        let key = self.key(path);
+        let resolved = self.root.join(&key);
+        self.files.entry(key).or_insert_with(|| {
+            let suppressions = FileSuppressions::from_file(&resolved).unwrap_or_default();
+            let used = vec![false; suppressions.directives.len()];
+            (suppressions, used)
+        })
+    }
+
+    /// Drop every diagnostic suppressed by a directive in the file it points at,
+    /// along with matches on the directive text itself. Each file is read and
+    /// parsed at most once.
+    pub fn filter(&mut self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|d| {
+                if d.path.is_empty() {
+                    return true;
+                }
+                let (suppressions, used) = self.file(Path::new(&d.path));
+                if suppressions.within_directive(d) {
+                    return false;
+                }
+                let suppressing = suppressions.suppressing(d);
+                for &i in &suppressing {
+                    used[i] = true;
+                }
+                suppressing.is_empty()
+            })
+            .collect()
+    }
+
+    /// Report directives in `paths` that suppressed nothing, each with a fix
+    /// deleting the directive (and the matching end of a begin/end pair).
+    /// Files not stored as UTF-8 get no fix, since its offsets wouldn't match the bytes.
+    pub fn unused(&mut self, paths: &HashSet<PathBuf>) -> Vec<Diagnostic> {
+        let mut ret = Vec::new();
+        // One entry per file however its path was spelled
+        let paths: BTreeMap<PathBuf, &str> = paths
+            .iter()
+            .filter_map(|path| Some((self.key(path), path.to_str()?)))
+            .collect();
+        for (key, path) in paths {
+            let resolved = self.root.join(&key);
+            if !resolved.is_file() {
+                continue;
+            }
+            let (suppressions, used) = self.file(&key);
+            let Ok(Contents::Text { text, encoding }) = encoding::read(&resolved) else {
+                continue;
+            };
+            let lines: Vec<&str> = text.lines().collect();
+
+            for (i, directive) in suppressions.directives.iter().enumerate() {
+                if used[i] || directive.kind == DirectiveKind::End {
+                    continue;
+                }
+                let Some(line) = lines.get(directive.line as usize) else {
+                    continue;
+                };
+                let mut replacements = vec![removal(directive, line)];
+                if let Some(end) = directive.partner.map(|p| &suppressions.directives[p]) {
+                    if let Some(end_line) = lines.get(end.line as usize) {
+                        replacements.push(removal(end, end_line));
+                    }
+                }
+                let replacements = encoding.is_utf8().then_some(replacements);
+                ret.push(Diagnostic {
+                    path: path.to_string(),
+                    range: Some(Range {
+                        start: Position {
+                            line: directive.line,
+                            character: directive.start as u64,
+                            unit: ColumnUnit::Utf8,
+                        },
+                        end: Position {
+                            line: directive.line,
+                            character: directive.end as u64,
+                            unit: ColumnUnit::Utf8,
+                        },
+                    }),
+                    severity: Severity::Warning,
+                    code: "toolbox-unused-suppression".to_string(),
+                    message: format!(
+                        "'{}' does not suppress any issue",
+                        &line[directive.start..directive.end]
+                    ),
+                    replacements,
+                });
+            }
+        }
+        ret
+    }
+}
+
+/// A replacement deleting `directive`. When nothing but a comment marker would
+/// be left behind, the whole line is deleted instead.
+fn removal(directive: &Directive, line: &str) -> Replacement {
+    let remainder = format!("{}{}", &line[..directive.start], &line[directive.end..]);
+    let whole_line = matches!(
+        remainder.trim(),
+        "" | "//" | "#" | "--" | ";" | "/*  */" | "/**/" | "<!--  -->" | "<!---->"
+    );
+    let (start, end) = if whole_line {
+        (
+            Position {
+                line: directive.line,
+                character: 0,
+                unit: ColumnUnit::Utf8,
+            },
+            Position {
+                line: directive.line + 1,
+                character: 0,
+                unit: ColumnUnit::Utf8,
+            },
+        )
+    } else {
+        (
+            Position {
+                line: directive.line,
+                character: directive.start as u64,
+                unit: ColumnUnit::Utf8,
+            },
+            Position {
+                line: directive.line,
+                character: directive.end as u64,
+                unit: ColumnUnit::Utf8,
+            },
+        )
+    };
+    Replacement {
+        path: None,
+        deleted_region: Range { start, end },
+        inserted_content: String::new(),
+    }
+}
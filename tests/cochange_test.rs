@@ -0,0 +1,106 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML: &str = r#"
+[[cochange]]
+when = "Cargo.toml"
+require = "Cargo.lock"
+
+[[cochange]]
+when = "src/**/*.rs"
+require = "tests/$1${2}_test.rs"
+severity = "warning"
+"#;
+
+#[test]
+fn required_changes_are_reported() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("Cargo.toml", b"[package]\n");
+    test_repo.write("Cargo.lock", b"version = 3\n");
+    test_repo.write("src/net/client.rs", b"fn a() {}\n");
+    test_repo.write("src/util.rs", b"fn b() {}\n");
+    test_repo.write("tests/util_test.rs", b"fn t() {}\n");
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("initial");
+
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write("Cargo.toml", b"[package]\nname = \"x\"\n");
+    test_repo.write("src/net/client.rs", b"fn a() { b() }\n");
+    test_repo.write("src/util.rs", b"fn b() { c() }\n");
+    test_repo.write("tests/util_test.rs", b"fn t() { b() }\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "cochange-required",
+        "Expected a change in 'Cargo.lock' because 'Cargo.toml' was modified",
+        Some("Cargo.toml")
+    ));
+    assert!(horton.has_result(
+        "cochange-required",
+        "Expected a change in 'tests/net/client_test.rs' because 'src/net/client.rs' was modified",
+        Some("src/net/client.rs")
+    ));
+    assert!(!horton.has_result("cochange-required", "", Some("src/util.rs")));
+
+    Ok(())
+}
+
+#[test]
+fn wildcards_inside_braces_match_changed_files() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("web/app.tsx", b"export {}\n");
+    test_repo.write("web/CHANGELOG.md", b"# Changes\n");
+    test_repo.write("packages/gql/schema/user.graphql", b"type User\n");
+    test_repo.write("api/order.graphql", b"type Order\n");
+    test_repo.write("docs/user.md", b"# User\n");
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("initial");
+
+    test_repo.set_toolbox_toml(
+        r#"
+[[cochange]]
+when = "web/{*.ts,*.tsx}"
+require = "web/CHANGELOG.md"
+
+[[cochange]]
+when = "{**/schema,api}/*.graphql"
+require = "docs/$2.md"
+"#,
+    );
+    test_repo.write("web/app.tsx", b"export const a = 1\n");
+    test_repo.write(
+        "packages/gql/schema/user.graphql",
+        b"type User { id: ID }\n",
+    );
+    test_repo.write("docs/user.md", b"# User\n\nHas an id.\n");
+    test_repo.write("api/order.graphql", b"type Order { id: ID }\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        horton.has_result(
+            "cochange-required",
+            "Expected a change in 'web/CHANGELOG.md' because 'web/app.tsx' was modified",
+            Some("web/app.tsx")
+        ),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result(
+        "cochange-required",
+        "Expected a change in 'docs/order.md' because 'api/order.graphql' was modified",
+        Some("api/order.graphql")
+    ));
+    assert!(!horton.has_result(
+        "cochange-required",
+        "",
+        Some("packages/gql/schema/user.graphql")
+    ));
+
+    Ok(())
+}
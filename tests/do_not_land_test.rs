@@ -58,3 +58,208 @@ fn honor_disabled_in_config() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn dated_marker_allowed_until_expiry() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("alpha.foo", "DONOTLAND(2026-06-30): demo flag\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-06-30"])?;
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("do-not-land"));
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-07-03"])?;
+    assert!(horton.has_result(
+        "do-not-land",
+        "Found 'DONOTLAND', expired on 2026-06-30 (3 days overdue)",
+        Some("alpha.foo")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn marker_in_file_path() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("scratch_donotland.py", "print('hi')\n".as_bytes());
+    test_repo.write("tmp-do-not-land/notes.txt", "notes\n".as_bytes());
+    test_repo.write(
+        "scratch-DNL-not-really.png",
+        include_bytes!("trunk-logo.png"),
+    );
+    test_repo.write("do_not_land.png", include_bytes!("trunk-logo.png"));
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "do-not-land",
+        "Found 'donotland' in file path",
+        Some("scratch_donotland.py")
+    ));
+    assert!(horton.has_result(
+        "do-not-land",
+        "Found 'do-not-land' in file path",
+        Some("tmp-do-not-land/notes.txt")
+    ));
+    assert!(horton.has_result(
+        "do-not-land",
+        "Found 'do_not_land' in file path",
+        Some("do_not_land.png")
+    ));
+    assert!(!horton.has_result("do-not-land", "", Some("scratch-DNL-not-really.png")));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn marker_in_checkout_path_is_ignored() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("alpha.foo", "lorem ipsum\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    // The same checkout, reached through a directory named like a marker
+    let parent = tempfile::tempdir()?;
+    let checkout = parent.path().join("do-not-land");
+    std::os::unix::fs::symlink(test_repo.path(), &checkout)?;
+    let absolute = checkout.join("alpha.foo");
+
+    let horton = test_repo.run_horton_with_args(&[
+        "--workspace",
+        checkout.to_str().unwrap(),
+        absolute.to_str().unwrap(),
+    ])?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result("do-not-land", "Found 'do-not-land' in file path", None),
+        "{}",
+        horton
+    );
+
+    Ok(())
+}
+
+#[test]
+fn marker_in_commits_and_branch() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[donotland]\ncommits = true\n");
+    test_repo.git_commit_all("add config");
+
+    test_repo.git_checkout_new_branch("dnl/experiment");
+    test_repo.write("alpha.foo", "lorem ipsum\n".as_bytes());
+    test_repo.git_commit_all("WIP: DO NOT LAND\n\nstill debugging");
+    test_repo.write("beta.foo", "dolor\n".as_bytes());
+    test_repo.git_commit_all("Add beta");
+
+    let horton = test_repo.run_horton_with("main", "sarif", true)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "do-not-land-commit",
+        "Found 'dnl' in branch name 'dnl/experiment'",
+        None
+    ));
+    assert!(horton.has_result("do-not-land-commit", "\"WIP: DO NOT LAND\"", None));
+    assert!(!horton.has_result("do-not-land-commit", "Add beta", None));
+
+    Ok(())
+}
+
+#[test]
+fn commit_markers_are_reported_once_and_exactly() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[donotland]\ncommits = true\n");
+    test_repo.git_commit_all("add config");
+
+    test_repo.git_checkout_new_branch("feature/dnl-cleanup");
+    test_repo.write("alpha.foo", "lorem ipsum\n".as_bytes());
+    test_repo.write("beta.foo", "dolor\n".as_bytes());
+    test_repo.git_commit_all("Remove dnl-era helpers\n\nDNL");
+
+    let run = |file: &str| {
+        test_repo.run_toolbox_args(&["--upstream", "main", "--output-format", "text", file])
+    };
+
+    // Only the batch with the first changed file reports the commit
+    let first = run("alpha.foo")?;
+    assert_eq!(first.exit_code, Some(0), "{}", first);
+    assert_eq!(
+        first.stdout.matches("Found 'DNL' in commit").count(),
+        1,
+        "{}",
+        first
+    );
+    assert!(!first.stdout.contains("branch name"), "{}", first);
+    let second = run("beta.foo")?;
+    assert!(!second.stdout.contains("Found 'DNL'"), "{}", second);
+
+    Ok(())
+}
+
+#[test]
+fn commit_markers_skip_config_file_as_anchor() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[donotland]\ncommits = true\n");
+    test_repo.git_commit_all("add config");
+
+    // The config sorts before every other changed file
+    test_repo.git_checkout_new_branch("dnl/experiment");
+    test_repo.set_toolbox_toml("[donotland]\ncommits = true\n\n[todo]\nenabled = false\n");
+    test_repo.write("zeta.foo", "lorem ipsum\n".as_bytes());
+    test_repo.git_commit_all("WIP DO NOT LAND");
+
+    let horton = test_repo.run_horton_with("main", "sarif", true)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        horton.has_result(
+            "do-not-land-commit",
+            "Found 'dnl' in branch name 'dnl/experiment'",
+            Some("zeta.foo")
+        ),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result(
+        "do-not-land-commit",
+        "Found 'DO NOT LAND' in commit",
+        Some("zeta.foo")
+    ));
+    assert!(!horton.has_result("do-not-land-commit", "", Some("toolbox.toml")));
+
+    Ok(())
+}
+
+#[test]
+fn commits_not_checked_by_default() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.git_checkout_new_branch("dnl/experiment");
+    test_repo.write("alpha.foo", "lorem ipsum\n".as_bytes());
+    test_repo.git_commit_all("DO-NOT-LAND");
+
+    let horton = test_repo.run_horton_with("main", "sarif", true)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("do-not-land-commit"));
+
+    Ok(())
+}
+
+#[test]
+fn unreadable_file_is_a_rule_error() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("src/alpha.foo", "lorem ipsum\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    // A directory exists but can't be read as a file
+    let horton = test_repo.run_horton_with_args(&["src"])?;
+
+    assert_ne!(horton.exit_code, Some(0));
+    assert!(horton.has_result("toolbox-rule-error", "rule 'pls_no_land' failed", None));
+
+    Ok(())
+}
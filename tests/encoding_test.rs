@@ -0,0 +1,129 @@
+// trunk-ignore-all(trunk-toolbox/do-not-land)
+mod integration_testing;
+use integration_testing::TestRepo;
+
+fn utf16le_with_bom(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        bytes.extend(unit.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn scans_utf16_and_latin1_files() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[nocurlyquotes]\nenabled = true\n");
+    test_repo.write(
+        "app.rc",
+        &utf16le_with_bom("// DONOTLAND\r\nSTRING \u{201C}hi\u{201D}\r\n"),
+    );
+    test_repo.write("legacy.c", b"/* caf\xE9 */\n// DONOTLAND\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("do-not-land", "Found 'DONOTLAND'", Some("app.rc")));
+    assert!(horton.has_result("no-curly-quotes", "", Some("app.rc")));
+    assert!(horton.has_result("do-not-land", "Found 'DONOTLAND'", Some("legacy.c")));
+    assert!(!horton.has_result_with_rule_id("toolbox-undecodable-file"));
+
+    Ok(())
+}
+
+#[test]
+fn undecodable_file_is_skipped_with_note() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[nocurlyquotes]\nenabled = true\n");
+    // Windows-1252 curly quotes are C1 control bytes in Latin-1
+    test_repo.write("notes.txt", b"\x93DONOTLAND\x94\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("toolbox-rule-error"));
+    assert!(horton.has_result(
+        "toolbox-undecodable-file",
+        "pls_no_land skipped this file: it is neither UTF-8",
+        Some("notes.txt")
+    ));
+    assert!(horton.has_result(
+        "toolbox-undecodable-file",
+        "no_curly_quotes skipped this file",
+        Some("notes.txt")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn columns_are_converted_against_the_workspace() -> anyhow::Result<()> {
+    use horton::diagnostic::{ColumnConverter, ColumnUnit, Diagnostic, Position, Range, Severity};
+
+    let test_repo = TestRepo::make()?;
+    test_repo.write(
+        "src/caf\u{e9}.txt",
+        "caf\u{e9} \u{1F600} DONOTLAND\n".as_bytes(),
+    );
+
+    let position = |character| Position {
+        line: 0,
+        character,
+        unit: ColumnUnit::Utf8,
+    };
+    let mut diagnostics = vec![Diagnostic {
+        path: "src/caf\u{e9}.txt".to_string(),
+        range: Some(Range {
+            start: position(11),
+            end: position(20),
+        }),
+        severity: Severity::Error,
+        code: "do-not-land".to_string(),
+        message: String::new(),
+        replacements: None,
+    }];
+
+    // The test process runs elsewhere, so the relative path only resolves in the workspace
+    ColumnConverter::new(Some(test_repo.path())).convert(&mut diagnostics, ColumnUnit::Utf16);
+
+    let range = diagnostics[0].range.as_ref().unwrap();
+    assert_eq!(range.start.character, 8);
+    assert_eq!(range.end.character, 17);
+
+    Ok(())
+}
+
+#[test]
+fn suppressions_in_utf16_files() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[todo]\nenabled = true\n");
+    test_repo.write(
+        "a.rc",
+        &utf16le_with_bom(
+            "// TODO: x toolbox-ignore(todo)\r\n// toolbox-ignore(no-curly-quotes)\r\n",
+        ),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result("todo", "", Some("a.rc")), "{}", horton);
+    assert!(horton.has_result(
+        "toolbox-unused-suppression",
+        "'toolbox-ignore(no-curly-quotes)' does not suppress any issue",
+        Some("a.rc")
+    ));
+    // Offsets into the decoded text don't line up with the UTF-16 bytes
+    let unused = horton
+        .runs()
+        .into_iter()
+        .flat_map(|run| run.results.unwrap_or_default())
+        .find(|r| r.rule_id.as_deref() == Some("toolbox-unused-suppression"))
+        .expect("unused suppression");
+    assert!(unused.fixes.unwrap_or_default().is_empty());
+
+    Ok(())
+}
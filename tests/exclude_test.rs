@@ -0,0 +1,101 @@
+// trunk-ignore-all(trunk-toolbox/do-not-land,trunk-toolbox/todo)
+mod integration_testing;
+use integration_testing::TestRepo;
+
+#[test]
+fn global_exclude() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[global]
+exclude = ["vendor/**"]
+"#,
+    );
+    test_repo.write("vendor/lib.rs", "// DONOTLAND\n".as_bytes());
+    test_repo.write("src/lib.rs", "// DONOTLAND\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("do-not-land", "", Some("src/lib.rs")));
+    assert!(!horton.has_result("do-not-land", "", Some("vendor/lib.rs")));
+    assert!(!horton.has_result("do-not-land", "", Some("toolbox.toml")));
+
+    Ok(())
+}
+
+#[test]
+fn per_rule_include_and_exclude() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[donotland]
+exclude = ["**/__snapshots__/**"]
+
+[todo]
+enabled = true
+include = ["src/**"]
+"#,
+    );
+    test_repo.write("src/__snapshots__/app.snap", "DONOTLAND\nTODO\n".as_bytes());
+    test_repo.write("tests/fixture.txt", "DONOTLAND\nTODO\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result("do-not-land", "", Some("src/__snapshots__/app.snap")));
+    assert!(horton.has_result("todo", "", Some("src/__snapshots__/app.snap")));
+    assert!(horton.has_result("do-not-land", "", Some("tests/fixture.txt")));
+    assert!(!horton.has_result("todo", "", Some("tests/fixture.txt")));
+
+    Ok(())
+}
+
+#[test]
+fn config_file_skipped_by_default() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[[tokens]]
+code = "nocommit"
+pattern = "NOCOMMIT"
+"#,
+    );
+    test_repo.write("alpha.foo", "NOCOMMIT\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("nocommit", "", Some("alpha.foo")));
+    assert!(!horton.has_result("nocommit", "", Some("toolbox.toml")));
+
+    Ok(())
+}
+
+#[test]
+fn config_file_skipped_with_custom_global_exclude() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[global]
+exclude = ["vendor/**"]
+
+[[tokens]]
+code = "nocommit"
+pattern = "NOCOMMIT"
+"#,
+    );
+    test_repo.write("alpha.foo", "NOCOMMIT\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("nocommit", "", Some("alpha.foo")));
+    assert!(!horton.has_result("nocommit", "", Some("toolbox.toml")));
+
+    Ok(())
+}
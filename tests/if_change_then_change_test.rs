@@ -70,7 +70,7 @@ fn unmodified_block_and_preceding_lines_unchanged() -> anyhow::Result<()> {
         f
     "#;
 
-    assert_no_expected_changes(&before, &after)
+    assert_no_expected_changes(before, after)
 }
 
 #[test]
@@ -100,7 +100,7 @@ fn unmodified_block_and_preceding_lines_changed() -> anyhow::Result<()> {
         f
     "#;
 
-    assert_no_expected_changes(&before, &after)
+    assert_no_expected_changes(before, after)
 }
 
 #[test]
@@ -125,7 +125,7 @@ fn unmodified_block_and_preceding_lines_deleted() -> anyhow::Result<()> {
         f
     "#;
 
-    assert_no_expected_changes(&before, &after)
+    assert_no_expected_changes(before, after)
 }
 
 #[test]
@@ -152,7 +152,7 @@ fn unmodified_block_and_otehr_lines_modified() -> anyhow::Result<()> {
         ffff
     "#;
 
-    assert_no_expected_changes(&before, &after)
+    assert_no_expected_changes(before, after)
 }
 
 #[test]
@@ -173,7 +173,7 @@ fn modified_block_and_preceding_lines_unchanged() -> anyhow::Result<()> {
         c
     "#;
 
-    assert_expected_change_in_constant_foo(&before, &after)
+    assert_expected_change_in_constant_foo(before, after)
 }
 
 #[test]
@@ -194,7 +194,7 @@ fn modified_block_and_preceding_line_count_unchanged() -> anyhow::Result<()> {
         c
     "#;
 
-    assert_expected_change_in_constant_foo(&before, &after)
+    assert_expected_change_in_constant_foo(before, after)
 }
 
 #[test]
@@ -217,7 +217,7 @@ fn modified_block_and_preceding_line_count_decreased() -> anyhow::Result<()> {
         c
     "#;
 
-    assert_expected_change_in_constant_foo(&before, &after)
+    assert_expected_change_in_constant_foo(before, after)
 }
 
 #[test]
@@ -240,7 +240,7 @@ fn modified_block_and_preceding_line_count_increased() -> anyhow::Result<()> {
         c
     "#;
 
-    assert_expected_change_in_constant_foo(&before, &after)
+    assert_expected_change_in_constant_foo(before, after)
 }
 
 #[test]
@@ -381,7 +381,7 @@ fn verify_find_ictc_blocks() {
 
     let result = find_ictc_blocks(&PathBuf::from("tests/if_change_then_change/no_ictc.file"));
     assert!(result.is_ok());
-    assert!(result.unwrap().len() == 0, "should find no ictc block");
+    assert!(result.unwrap().is_empty(), "should find no ictc block");
 
     let result = find_ictc_blocks(&PathBuf::from(
         "tests/if_change_then_change/multiple_ictc.file",
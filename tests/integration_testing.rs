@@ -113,20 +113,20 @@ impl HortonOutput {
 impl fmt::Display for HortonOutput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.exit_code {
-            Some(c) => write!(f, "toolbox exit code was {}\n", c)?,
-            None => write!(f, "toolbox exited abnormally\n")?,
+            Some(c) => writeln!(f, "toolbox exit code was {}", c)?,
+            None => writeln!(f, "toolbox exited abnormally")?,
         };
 
         if self.stdout.is_empty() {
-            write!(f, "toolbox stdout: (empty)\n")?;
+            writeln!(f, "toolbox stdout: (empty)")?;
         } else {
-            write!(f, "toolbox stdout:\n{}\n", self.stdout.as_str())?;
+            writeln!(f, "toolbox stdout:\n{}", self.stdout.as_str())?;
         }
 
         if self.stderr.is_empty() {
-            write!(f, "toolbox stderr: (empty)\n")
+            writeln!(f, "toolbox stderr: (empty)")
         } else {
-            write!(f, "toolbox stderr:\n{}\n", self.stderr.as_str())
+            writeln!(f, "toolbox stderr:\n{}", self.stderr.as_str())
         }
     }
 }
@@ -184,6 +184,11 @@ impl TestRepo {
         Ok(TestRepo { dir })
     }
 
+    #[allow(dead_code)]
+    pub fn path(&self) -> &std::path::Path {
+        self.dir.path()
+    }
+
     pub fn write(&self, relpath: &str, data: &[u8]) {
         let path = {
             let mut path = self.dir.path().to_path_buf();
@@ -194,10 +199,10 @@ impl TestRepo {
         // Create the directory hierarchy if needed
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
-                .expect(format!("Unable to create directories for {:#?}", parent).as_str());
+                .unwrap_or_else(|_| panic!("Unable to create directories for {:#?}", parent));
         }
 
-        fs::write(&path, data).expect(format!("Unable to write {:#?}", path).as_str());
+        fs::write(&path, data).unwrap_or_else(|_| panic!("Unable to write {:#?}", path));
     }
 
     #[allow(dead_code)]
@@ -207,7 +212,7 @@ impl TestRepo {
             path.push(relpath);
             path
         };
-        fs::remove_file(&path).expect(format!("Unable to delete {:#?}", path).as_str());
+        fs::remove_file(&path).unwrap_or_else(|_| panic!("Unable to delete {:#?}", path));
     }
 
     pub fn git_add_all(&self) -> anyhow::Result<()> {
@@ -235,6 +240,19 @@ impl TestRepo {
         assert!(output.status.success(), "Git commit failed");
     }
 
+    #[allow(dead_code)]
+    pub fn git_checkout_new_branch(&self, name: &str) {
+        let output = Command::new("git")
+            .arg("checkout")
+            .arg("-b")
+            .arg(name)
+            .current_dir(self.dir.path())
+            .output()
+            .expect("Failed to execute git command");
+
+        assert!(output.status.success(), "Git checkout failed");
+    }
+
     #[allow(dead_code)]
     pub fn run_horton(&self) -> anyhow::Result<HortonOutput> {
         self.run_horton_with("HEAD", "sarif", true)
@@ -256,9 +274,16 @@ impl TestRepo {
             format,
             ResultsMode::from_flag(write_results_to_file),
             None,
+            &[],
         )
     }
 
+    /// Like `run_horton`, with additional command line flags (e.g. `--today`).
+    #[allow(dead_code)]
+    pub fn run_horton_with_args(&self, args: &[&str]) -> anyhow::Result<HortonOutput> {
+        self.run_horton_inner("HEAD", "sarif", ResultsMode::Auto, None, args)
+    }
+
     #[allow(dead_code)]
     pub fn run_horton_customized(
         &self,
@@ -271,7 +296,24 @@ impl TestRepo {
             Some(p) => ResultsMode::Explicit(p.to_path_buf()),
             None => ResultsMode::None,
         };
-        self.run_horton_inner(upstream_ref, format, results_mode, cache_dir)
+        self.run_horton_inner(upstream_ref, format, results_mode, cache_dir, &[])
+    }
+
+    /// Run toolbox in the test repo with arbitrary arguments (e.g. a subcommand);
+    /// nothing is written to a results file, so only stdout/stderr are populated.
+    #[allow(dead_code)]
+    pub fn run_toolbox_args(&self, args: &[&str]) -> anyhow::Result<HortonOutput> {
+        let output = Command::cargo_bin("trunk-toolbox")?
+            .args(args)
+            .current_dir(self.dir.path())
+            .output()?;
+
+        Ok(HortonOutput {
+            stdout: String::from_utf8(output.stdout)?,
+            stderr: String::from_utf8(output.stderr)?,
+            results: String::new(),
+            exit_code: output.status.code(),
+        })
     }
 
     fn run_horton_inner(
@@ -280,6 +322,7 @@ impl TestRepo {
         format: &str,
         results_mode: ResultsMode,
         cache_dir: Option<&str>,
+        extra_args: &[&str],
     ) -> anyhow::Result<HortonOutput> {
         let mut cmd = Command::cargo_bin("trunk-toolbox")?;
 
@@ -297,6 +340,7 @@ impl TestRepo {
         if let Some(dir) = cache_dir {
             cmd.arg("--cache-dir").arg(dir);
         }
+        cmd.args(extra_args);
         for path in files {
             cmd.arg(path);
         }
@@ -328,12 +372,12 @@ impl TestRepo {
             None => String::new(),
         };
 
-        return Ok(HortonOutput {
+        Ok(HortonOutput {
             stdout: String::from_utf8(output.stdout)?,
             stderr: String::from_utf8(output.stderr)?,
             results,
             exit_code: output.status.code(),
-        });
+        })
     }
 }
 
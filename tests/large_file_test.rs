@@ -0,0 +1,122 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[largefile]
+enabled = true
+max_kb = 1
+binary_globs = ["**/*.psd"]
+"#;
+
+#[test]
+fn new_large_and_binary_files_need_lfs() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("dump.sql", &[b'x'; 4096]);
+    test_repo.write("art/logo.psd", b"8BPS");
+    test_repo.write("small.txt", b"small\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "large-file",
+        "New file is 4 KB, over the 1 KB limit; track it with Git LFS by adding '/dump.sql filter=lfs diff=lfs merge=lfs -text' to .gitattributes",
+        Some("dump.sql")
+    ));
+    assert!(horton.has_result(
+        "binary-file",
+        "add '*.psd filter=lfs diff=lfs merge=lfs -text' to .gitattributes",
+        Some("art/logo.psd")
+    ));
+    assert!(!horton.has_result("large-file", "", Some("small.txt")));
+
+    Ok(())
+}
+
+#[test]
+fn lfs_tracked_and_existing_files_pass() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("old.sql", &[b'x'; 4096]);
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add old dump");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("old.sql", &[b'y'; 4096]);
+    test_repo.write(
+        ".gitattributes",
+        b"*.psd filter=lfs diff=lfs merge=lfs -text\n/new.sql filter=lfs diff=lfs merge=lfs -text\n",
+    );
+    test_repo.write("logo.psd", b"8BPS");
+    test_repo.write("new.sql", &[b'x'; 4096]);
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("large-file"), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("binary-file"), "{}", horton);
+
+    Ok(())
+}
+
+#[test]
+fn untracked_large_file_fix_appends_to_gitattributes() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write(
+        ".gitattributes",
+        b"*.psd filter=lfs diff=lfs merge=lfs -text",
+    );
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add gitattributes");
+
+    // Never staged, in a directory git doesn't know about yet
+    test_repo.write("data/dump[1]*.sql", &[b'x'; 4096]);
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        horton.has_result(
+            "large-file",
+            "adding 'data/dump\\[1]\\*.sql filter=lfs diff=lfs merge=lfs -text' to .gitattributes",
+            Some("data/dump[1]*.sql")
+        ),
+        "{}",
+        horton
+    );
+
+    let changes: Vec<_> = horton
+        .runs()
+        .into_iter()
+        .flat_map(|run| run.results.unwrap_or_default())
+        .filter(|result| result.rule_id.as_deref() == Some("large-file"))
+        .flat_map(|result| result.fixes.unwrap_or_default())
+        .flat_map(|fix| fix.artifact_changes)
+        .collect();
+    assert_eq!(changes.len(), 1, "{}", horton);
+    let change = &changes[0];
+    assert_eq!(
+        change.artifact_location.uri.as_deref(),
+        Some(".gitattributes")
+    );
+    let replacement = &change.replacements[0];
+    let region = &replacement.deleted_region;
+    assert_eq!(
+        (region.start_line, region.start_column),
+        (Some(1), Some(42))
+    );
+    assert_eq!(
+        replacement
+            .inserted_content
+            .as_ref()
+            .unwrap()
+            .text
+            .as_deref(),
+        Some("\ndata/dump\\[1]\\*.sql filter=lfs diff=lfs merge=lfs -text\n")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[licenseheader]
+enabled = true
+holder = "Acme Corp"
+template = """
+Copyright {year} {holder}
+SPDX-License-Identifier: MIT
+"""
+
+[[licenseheader.styles]]
+glob = "**/*.sql"
+prefix = "-- "
+"#;
+
+#[test]
+fn missing_headers_are_inserted_after_shebang() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("run.py", "#!/usr/bin/env python3\nimport os\n".as_bytes());
+    test_repo.write("schema.sql", "CREATE TABLE t (id INT);\n".as_bytes());
+    test_repo.write(
+        "ok.rs",
+        "// Copyright 2021 Acme Corp\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-10-19"])?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "license-header-missing",
+        "File is missing the license header",
+        Some("run.py")
+    ));
+    assert!(horton.has_fix_with_content(
+        "license-header-missing",
+        "# Copyright 2026 Acme Corp\n# SPDX-License-Identifier: MIT\n\n"
+    ));
+    assert!(horton.has_fix_with_content(
+        "license-header-missing",
+        "-- Copyright 2026 Acme Corp\n-- SPDX-License-Identifier: MIT\n\n"
+    ));
+    assert!(!horton.has_result("license-header-missing", "", Some("ok.rs")));
+    // Newly added files keep the year they were written with
+    assert!(!horton.has_result_with_rule_id("license-header-stale-year"));
+
+    Ok(())
+}
+
+#[test]
+fn stale_years_are_updated_in_modified_files() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    let header = "// Copyright 2019-2024 Acme Corp\n// SPDX-License-Identifier: MIT\n";
+    test_repo.write("lib.rs", format!("{}fn a() {{}}\n", header).as_bytes());
+    test_repo.write(
+        "untouched.rs",
+        format!("{}fn b() {{}}\n", header).as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add sources");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("lib.rs", format!("{}fn a() {{ b() }}\n", header).as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-10-19"])?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "license-header-stale-year",
+        "Copyright year '2019-2024' is out of date; expected '2019-2026'",
+        Some("lib.rs")
+    ));
+    assert!(horton.has_fix_with_content("license-header-stale-year", "2019-2026"));
+    assert!(!horton.has_result("license-header-stale-year", "", Some("untouched.rs")));
+
+    Ok(())
+}
+
+#[test]
+fn deleted_files_are_skipped() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("gone.py", "import os\n".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add files");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.delete("gone.py");
+    test_repo.write("keep.py", "import sys\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-10-19"])?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result_with_rule_id("toolbox-rule-error"),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result("license-header-missing", "", Some("keep.py")));
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[mergeconflict]
+enabled = true
+"#;
+
+#[test]
+fn conflict_hunks_are_reported() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write(
+        "config.yaml",
+        "name: app\n<<<<<<< HEAD\nreplicas: 2\n=======\nreplicas: 3\n>>>>>>> feature\n".as_bytes(),
+    );
+    test_repo.write(
+        "settings.json",
+        "{\n<<<<<<< ours\n  \"a\": 1\n||||||| base\n  \"a\": 0\n=======\n  \"a\": 2\n>>>>>>> theirs\n}\n"
+            .as_bytes(),
+    );
+    test_repo.write("docs.rst", "Title\n=======\n\nBody text.\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "merge-conflict-marker",
+        "Unresolved merge conflict on lines 2-6",
+        Some("config.yaml")
+    ));
+    assert!(horton.has_result(
+        "merge-conflict-marker",
+        "Unresolved merge conflict on lines 2-8",
+        Some("settings.json")
+    ));
+    assert!(!horton.has_result("merge-conflict-marker", "", Some("docs.rst")));
+
+    Ok(())
+}
+
+#[test]
+fn disabled_by_default() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write(
+        "config.yaml",
+        "<<<<<<< HEAD\na: 1\n=======\na: 2\n>>>>>>> feature\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result("merge-conflict-marker", "", Some("config.yaml")));
+
+    Ok(())
+}
+
+#[test]
+fn deleted_files_are_skipped() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("gone.txt", "gone\n".as_bytes());
+    test_repo.write("keep.txt", "keep\n".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add files");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.delete("gone.txt");
+    test_repo.write(
+        "keep.txt",
+        "<<<<<<< HEAD\nkeep\n=======\nkept\n>>>>>>> feature\n".as_bytes(),
+    );
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result_with_rule_id("toolbox-rule-error"),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result("merge-conflict-marker", "", Some("keep.txt")));
+
+    Ok(())
+}
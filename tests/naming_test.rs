@@ -0,0 +1,60 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML: &str = r#"
+[[naming]]
+glob = "src/components/**/*.tsx"
+case = "PascalCase"
+
+[[naming]]
+glob = "db/migrations/*"
+pattern = "^\\d{14}_[a-z_]+\\.sql$"
+severity = "warning"
+"#;
+
+#[test]
+fn names_are_checked_per_glob() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write("src/components/forms/user-card.tsx", b"export {}\n");
+    test_repo.write("src/components/Button.tsx", b"export {}\n");
+    test_repo.write("src/hooks/use-user.tsx", b"export {}\n");
+    test_repo.write("db/migrations/20260101120000_AddUsers.sql", b"\n");
+    test_repo.write("db/migrations/add_orders.sql", b"\n");
+    test_repo.write("db/migrations/20260102120000_add_orders.sql", b"\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "naming-convention",
+        "File name 'user-card.tsx' should be PascalCase (files matching 'src/components/**/*.tsx'); rename it to 'UserCard.tsx'",
+        Some("user-card.tsx")
+    ));
+    assert!(horton.has_result(
+        "naming-convention",
+        "rename it to '20260101120000_add_users.sql'",
+        Some("20260101120000_AddUsers.sql")
+    ));
+    // No rename can supply the missing timestamp, so the message says so
+    assert!(horton.has_result(
+        "naming-convention",
+        "File name 'add_orders.sql' should match '^\\d{14}_[a-z_]+\\.sql$' (files matching 'db/migrations/*'); no rename could be derived from the current name",
+        Some("migrations/add_orders.sql")
+    ));
+    assert!(!horton.has_result(
+        "naming-convention",
+        "rename it to",
+        Some("migrations/add_orders.sql")
+    ));
+    assert!(!horton.has_result("naming-convention", "", Some("Button.tsx")));
+    assert!(!horton.has_result("naming-convention", "", Some("use-user.tsx")));
+    assert!(!horton.has_result(
+        "naming-convention",
+        "",
+        Some("20260102120000_add_orders.sql")
+    ));
+
+    Ok(())
+}
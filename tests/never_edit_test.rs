@@ -102,7 +102,8 @@ fn honor_disabled_in_config() -> anyhow::Result<()> {
 
     assert_eq!(horton.exit_code, Some(0));
     assert!(!horton.has_result("never-edit-deleted", "", Some("src/locked/file.txt")));
-    assert!(horton.has_result("toolbox-perf", "1 files processed", None));
+    // The deleted file and the untracked config
+    assert!(horton.has_result("toolbox-perf", "2 files processed", None));
 
     Ok(())
 }
@@ -193,3 +194,65 @@ fn warn_for_config_not_protecting_anything() -> anyhow::Result<()> {
     assert!(horton.has_result_with_rule_id("never-edit-bad-config"));
     Ok(())
 }
+
+#[test]
+fn ls_lists_protected_files_and_unmatched_globs() -> anyhow::Result<()> {
+    let test_repo: TestRepo = TestRepo::make().unwrap();
+
+    test_repo.write("src/write_once.txt", "immutable text".as_bytes());
+    test_repo.write("src/write_many.txt", "mutable text".as_bytes());
+    test_repo.write("db/migrations/001.sql", "create table".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("create files");
+
+    let toml = r#"
+    [neveredit]
+    enabled = true
+    paths = ["**/write_once*", "db/migrations/**", "bad_path/**"]
+"#;
+    test_repo.set_toolbox_toml(toml);
+
+    let horton = test_repo.run_toolbox_args(&["never-edit", "ls"])?;
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton
+        .stdout
+        .contains("db/migrations/001.sql\tdb/migrations/**"));
+    assert!(horton.stdout.contains("src/write_once.txt\t**/write_once*"));
+    assert!(!horton.stdout.contains("src/write_many.txt"));
+    assert!(horton
+        .stdout
+        .contains("\"bad_path/**\" does not protect any existing files"));
+
+    let horton = test_repo.run_toolbox_args(&["never-edit", "ls", "--path", "src"])?;
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.stdout.contains("src/write_once.txt\t**/write_once*"));
+    assert!(!horton.stdout.contains("db/migrations/001.sql"));
+
+    Ok(())
+}
+
+#[test]
+fn protects_the_config_file() -> anyhow::Result<()> {
+    let test_repo: TestRepo = TestRepo::make().unwrap();
+
+    let toml = r#"
+    [neveredit]
+    enabled = true
+    paths = [".config/toolbox.toml", "locked.txt"]
+"#;
+    test_repo.set_toolbox_toml(toml);
+    test_repo.write("locked.txt", "immutable text".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("protect the config");
+
+    test_repo.set_toolbox_toml(&format!("{}\n[todo]\nenabled = true\n", toml));
+    test_repo.write("locked.txt", "edit the text".as_bytes());
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("never-edit-modified", "", Some(".config/toolbox.toml")));
+    assert!(horton.has_result("never-edit-modified", "", Some("locked.txt")));
+
+    Ok(())
+}
@@ -40,7 +40,8 @@ fn honor_disabled_in_config() -> anyhow::Result<()> {
 
     assert_eq!(horton.exit_code, Some(0));
     assert!(!horton.has_result("no-curly-quotes", "", Some("src/curly.txt")));
-    assert!(horton.has_result("toolbox-perf", "1 files processed", None));
+    // The edited file and the untracked config
+    assert!(horton.has_result("toolbox-perf", "2 files processed", None));
 
     Ok(())
 }
@@ -78,3 +79,56 @@ fn assert_find_curly_quotes() {
         ));
     }
 }
+
+#[test]
+fn code_only_mode_skips_prose() -> anyhow::Result<()> {
+    let test_repo: TestRepo = TestRepo::make().unwrap();
+
+    test_repo.set_toolbox_toml(
+        r#"
+[nocurlyquotes]
+enabled = true
+mode = "code-only"
+exclude = ["legacy/**"]
+"#,
+    );
+    test_repo.write("docs/guide.md", CURLY_QUOTES.as_bytes());
+    test_repo.write("NOTES.txt", CURLY_QUOTES.as_bytes());
+    test_repo.write("src/parse.py", "x = \u{201C}hi\u{201D}\n".as_bytes());
+    test_repo.write("legacy/parse.py", "x = \u{201C}hi\u{201D}\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("no-curly-quotes", "", Some("src/parse.py")));
+    assert!(!horton.has_result("no-curly-quotes", "", Some("docs/guide.md")));
+    assert!(!horton.has_result("no-curly-quotes", "", Some("NOTES.txt")));
+    assert!(!horton.has_result("no-curly-quotes", "", Some("legacy/parse.py")));
+
+    Ok(())
+}
+
+#[test]
+fn paths_limit_checked_files() -> anyhow::Result<()> {
+    let test_repo: TestRepo = TestRepo::make().unwrap();
+
+    test_repo.set_toolbox_toml(
+        r#"
+[nocurlyquotes]
+enabled = true
+paths = ["src/**"]
+"#,
+    );
+    test_repo.write("src/parse.py", "x = \u{201C}hi\u{201D}\n".as_bytes());
+    test_repo.write("scripts/parse.py", "x = \u{201C}hi\u{201D}\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("no-curly-quotes", "", Some("src/parse.py")));
+    assert!(!horton.has_result("no-curly-quotes", "", Some("scripts/parse.py")));
+
+    Ok(())
+}
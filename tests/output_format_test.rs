@@ -44,3 +44,39 @@ fn default_print() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn columns_are_utf16_code_units() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml("[nocurlyquotes]\nenabled = true\n");
+
+    // 'é' is 2 bytes and 1 UTF-16 unit; the emoji is 4 bytes, 1 code point and 2 UTF-16 units
+    test_repo.write(
+        "alpha.foo",
+        "\u{e9}\u{1F600} DONOTLAND\n\u{1F600} \u{201C}x\u{201D}\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        horton
+            .stdout
+            .contains("alpha.foo:0:4: Found 'DONOTLAND' (error)"),
+        "{}",
+        horton
+    );
+    assert!(
+        horton
+            .stdout
+            .contains("alpha.foo:1:3: Found curly quote on line 2 (error)"),
+        "{}",
+        horton
+    );
+
+    let horton = test_repo.run_horton()?;
+    let doc: serde_json::Value = serde_json::from_str(&horton.results)?;
+    assert_eq!(doc["runs"][0]["columnKind"], "utf16CodeUnits");
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[portablepaths]
+enabled = true
+max_length = 40
+"#;
+
+#[test]
+fn unportable_paths_are_reported() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("README.md", b"readme\n");
+    test_repo.write("Docs/intro.md", b"intro\n");
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add docs");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("readme.md", b"readme\n");
+    test_repo.write("docs/setup.md", b"setup\n");
+    test_repo.write("aux.txt", b"aux\n");
+    test_repo.write("notes./todo.md", b"todo\n");
+    test_repo.write("what?.txt", b"what\n");
+    test_repo.write("deeply/nested/directory/with/a/long/name.txt", b"long\n");
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "path-case-collision",
+        "'readme.md' and 'README.md' differ only in case",
+        Some("readme.md")
+    ));
+    assert!(horton.has_result(
+        "path-case-collision",
+        "'docs' and 'Docs' differ only in case",
+        Some("docs/setup.md")
+    ));
+    assert!(horton.has_result(
+        "path-reserved-name",
+        "'aux.txt' is a reserved device name on Windows",
+        Some("aux.txt")
+    ));
+    assert!(horton.has_result(
+        "path-trailing-dot-or-space",
+        "'notes.' ends with a dot or space",
+        Some("todo.md")
+    ));
+    assert!(horton.has_result(
+        "path-invalid-character",
+        "'what?.txt' contains '?'",
+        Some("what")
+    ));
+    assert!(horton.has_result(
+        "path-too-long",
+        "Path is 44 characters long, over the limit of 40",
+        Some("name.txt")
+    ));
+    assert!(!horton.has_result("path-reserved-name", "", Some("readme.md")));
+    assert!(!horton.has_result("path-too-long", "", Some("setup.md")));
+
+    Ok(())
+}
@@ -94,7 +94,7 @@ fn malformed_config_still_writes_results_file() -> anyhow::Result<()> {
                 rs.iter().any(|r| {
                     r.rule_id
                         .as_deref()
-                        .map_or(false, |id| id.starts_with("toolbox-"))
+                        .is_some_and(|id| id.starts_with("toolbox-"))
                 })
             })
             .unwrap_or(false)
@@ -0,0 +1,281 @@
+// trunk-ignore-all(trunk-toolbox/do-not-land,trunk-toolbox/todo)
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML: &str = r#"
+[todo]
+enabled = true
+
+[nocurlyquotes]
+enabled = true
+"#;
+
+fn run_text(test_repo: &TestRepo) -> anyhow::Result<String> {
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    Ok(horton.stdout)
+}
+
+#[test]
+fn same_line_and_next_line() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "alpha.foo",
+        concat!(
+            "DONOTLAND // toolbox-ignore(do-not-land)\n",
+            "// toolbox-ignore-next-line(trunk-toolbox/todo)\n",
+            "// TODO: suppressed\n",
+            "// TODO: reported\n",
+            "// toolbox-ignore-next-line(todo)\n",
+            "DONOTLAND\n",
+        )
+        .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(!stdout.contains("alpha.foo:0:"), "{}", stdout);
+    assert!(!stdout.contains("alpha.foo:2:"), "{}", stdout);
+    assert!(stdout.contains("alpha.foo:3:3: Found 'TODO'"), "{}", stdout);
+    // next-line(todo) does not cover do-not-land
+    assert!(
+        stdout.contains("alpha.foo:5:0: Found 'DONOTLAND'"),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn begin_end_range() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "alpha.foo",
+        concat!(
+            "# toolbox-ignore-begin(todo, no-curly-quotes)\n",
+            "# TODO: one\n",
+            "print(“quoted”)\n",
+            "# toolbox-ignore-end(todo, no-curly-quotes)\n",
+            "# TODO: two\n",
+        )
+        .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(!stdout.contains("alpha.foo:1:"), "{}", stdout);
+    assert!(!stdout.contains("alpha.foo:2:"), "{}", stdout);
+    assert!(stdout.contains("alpha.foo:4:2: Found 'TODO'"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn file_level_all() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "alpha.foo",
+        "// toolbox-ignore-all(todo)\n// TODO: one\nDONOTLAND\n".as_bytes(),
+    );
+    test_repo.write(
+        "beta.foo",
+        "// toolbox-ignore-all\n// TODO: one\nDONOTLAND\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(!stdout.contains("alpha.foo:1:"), "{}", stdout);
+    assert!(
+        stdout.contains("alpha.foo:2:0: Found 'DONOTLAND'"),
+        "{}",
+        stdout
+    );
+    // Hiding everything in a file needs a code list
+    assert!(stdout.contains("beta.foo:1:3: Found 'TODO'"), "{}", stdout);
+    assert!(
+        stdout.contains("beta.foo:2:0: Found 'DONOTLAND'"),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn directives_outside_comments_are_ignored() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "alpha.rs",
+        concat!(
+            "let pat = \"toolbox-ignore-all(todo)\";\n",
+            "let next = \"toolbox-ignore-next-line\";\n",
+            "// TODO: reported\n",
+            "// TODO: suppressed toolbox-ignore(todo)\n",
+        )
+        .as_bytes(),
+    );
+    // Files the lexer doesn't know need a comment marker before the directive
+    test_repo.write(
+        "beta.foo",
+        "pat = toolbox-ignore-all(todo)\nTODO: reported\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(stdout.contains("alpha.rs:2:3: Found 'TODO'"), "{}", stdout);
+    assert!(!stdout.contains("alpha.rs:3:"), "{}", stdout);
+    assert!(stdout.contains("beta.foo:1:0: Found 'TODO'"), "{}", stdout);
+    assert!(!stdout.contains("does not suppress"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn unused_suppressions_are_reported() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "alpha.foo",
+        concat!(
+            "// toolbox-ignore-next-line(todo)\n",
+            "// TODO: suppressed\n",
+            "// toolbox-ignore-next-line(todo)\n",
+            "nothing to see here\n",
+            "x = 1 # toolbox-ignore(do-not-land)\n",
+            "# toolbox-ignore-begin(no-curly-quotes)\n",
+            "# toolbox-ignore-end(no-curly-quotes)\n",
+        )
+        .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(!stdout.contains("alpha.foo:0:"), "{}", stdout);
+    assert!(
+        stdout.contains(
+            "alpha.foo:2:3: 'toolbox-ignore-next-line(todo)' does not suppress any issue"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("alpha.foo:4:8: 'toolbox-ignore(do-not-land)' does not suppress any issue"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "alpha.foo:5:2: 'toolbox-ignore-begin(no-curly-quotes)' does not suppress any issue"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("alpha.foo:6:"), "{}", stdout);
+
+    let horton = test_repo.run_horton()?;
+    let runs = horton.runs();
+    let results = runs[0].results.as_ref().unwrap();
+    let unused_inline = results
+        .iter()
+        .find(|r| {
+            r.rule_id.as_deref() == Some("toolbox-unused-suppression")
+                && r.message
+                    .text
+                    .as_deref()
+                    .unwrap()
+                    .contains("toolbox-ignore(do-not-land)")
+        })
+        .expect("unused inline suppression");
+    // Only the directive is deleted when code precedes it on the line
+    let region = &unused_inline.fixes.as_ref().unwrap()[0].artifact_changes[0].replacements[0]
+        .deleted_region;
+    assert_eq!(region.start_line, Some(5));
+    assert_eq!(region.start_column, Some(9));
+    assert_eq!(region.end_column, Some(36));
+
+    let unused_range = results
+        .iter()
+        .find(|r| {
+            r.rule_id.as_deref() == Some("toolbox-unused-suppression")
+                && r.message.text.as_deref().unwrap().contains("begin")
+        })
+        .expect("unused begin suppression");
+    // Both comment lines of the pair are deleted outright
+    let replacements = &unused_range.fixes.as_ref().unwrap()[0].artifact_changes[0].replacements;
+    assert_eq!(replacements.len(), 2);
+    assert_eq!(replacements[0].deleted_region.start_line, Some(6));
+    assert_eq!(replacements[0].deleted_region.end_line, Some(7));
+    assert_eq!(replacements[1].deleted_region.start_line, Some(7));
+    assert_eq!(replacements[1].deleted_region.end_line, Some(8));
+
+    Ok(())
+}
+
+#[test]
+fn unused_suppressions_can_be_disabled() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[unusedsuppressions]
+enabled = false
+"#,
+    );
+    test_repo.write("alpha.foo", "x = 1 # toolbox-ignore(todo)\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+    assert!(!horton.has_result_with_rule_id("toolbox-unused-suppression"));
+
+    Ok(())
+}
+
+#[test]
+fn markdown_prose_is_not_a_directive() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write(
+        "README.md",
+        concat!(
+            "Silence a line with toolbox-ignore(no-curly-quotes), for example:\n",
+            "```python\n",
+            "x = 1  # toolbox-ignore(no-curly-quotes)\n",
+            "```\n",
+            "<!-- toolbox-ignore-next-line(todo) -->\n",
+            "TODO: hidden\n",
+        )
+        .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let stdout = run_text(&test_repo)?;
+    assert!(!stdout.contains("README.md"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn unused_suppressions_are_reported_once_per_file() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write("alpha.foo", "x = 1 # toolbox-ignore(todo)\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    // The same file under a second spelling
+    let horton = test_repo.run_horton_with_args(&["./alpha.foo"])?;
+    let runs = horton.runs();
+    let unused = runs[0]
+        .results
+        .as_ref()
+        .unwrap()
+        .iter()
+        .filter(|r| r.rule_id.as_deref() == Some("toolbox-unused-suppression"))
+        .count();
+    assert_eq!(unused, 1, "{}", horton);
+
+    Ok(())
+}
@@ -81,3 +81,393 @@ fn default_disabled_in_config() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn require_ticket() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    require_ticket = "[A-Z]+-\\d+"
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write(
+        "alpha.foo",
+        "// TODO(ABC-123): tracked\n// TODO: untracked\n// TODO(alice): owner only\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.foo:0:"));
+    assert!(horton
+        .stdout
+        .contains("alpha.foo:1:3: 'TODO' is missing a ticket reference"));
+    assert!(horton
+        .stdout
+        .contains("alpha.foo:2:3: 'TODO' is missing a ticket reference"));
+    assert!(!horton.stdout.contains("Found 'TODO'"));
+
+    let horton = test_repo.run_horton()?;
+    assert!(horton.has_result_with_rule_id("todo-missing-ticket"));
+    assert!(!horton.has_result_with_rule_id("todo"));
+
+    Ok(())
+}
+
+#[test]
+fn require_owner() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    require_owner = true
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write(
+        "alpha.foo",
+        "// TODO(alice): owned\n// TODO(ABC-123): ticket only\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.foo:0:"));
+    assert!(horton
+        .stdout
+        .contains("alpha.foo:1:3: 'TODO' is missing an owner"));
+
+    Ok(())
+}
+
+#[test]
+fn ticket_index_flags_stale_tickets() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    require_ticket = "[A-Z]+-\\d+"
+    ticket_index = "tickets.json"
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write("tickets.json", r#"["ABC-1", "ABC-2"]"#.as_bytes());
+    test_repo.write(
+        "alpha.foo",
+        "// TODO(ABC-1): open\n// TODO(ABC-9): closed\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.foo:0:"));
+    assert!(horton.stdout.contains(
+        "alpha.foo:1:3: 'TODO' references ABC-9, which is closed or unknown in tickets.json"
+    ));
+
+    let horton = test_repo.run_horton()?;
+    assert!(horton.has_result("todo-stale-ticket", "ABC-9", Some("alpha.foo")));
+
+    Ok(())
+}
+
+#[test]
+fn missing_ticket_index_is_a_rule_error() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    ticket_index = "missing.json"
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write("alpha.foo", "// TODO(ABC-1): open\n".as_bytes());
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton()?;
+
+    assert_ne!(horton.exit_code, Some(0));
+    assert!(horton.has_result("toolbox-rule-error", "todo.ticket_index", None));
+
+    Ok(())
+}
+
+#[test]
+fn dated_todos_expire() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    expiry_warning_days = 7
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write(
+        "alpha.foo",
+        "// TODO(2026-12-31): later\n// TODO(expires=2026-06-05): soon\n// TODO(2026-05-01): past\n"
+            .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with_args(&["--today", "2026-06-01"])?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result("todo", "", None));
+    assert!(horton.has_result(
+        "todo-expiring",
+        "'TODO' expires on 2026-06-05 (in 4 days)",
+        Some("alpha.foo")
+    ));
+    assert!(horton.has_result(
+        "todo-expired",
+        "'TODO' expired on 2026-05-01 (31 days overdue)",
+        Some("alpha.foo")
+    ));
+
+    let horton = test_repo.run_horton_with_args(&["--today", "2027-01-02"])?;
+    assert!(horton.has_result(
+        "todo-expired",
+        "'TODO' expired on 2026-12-31 (2 days overdue)",
+        Some("alpha.foo")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_today_is_an_error() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("alpha.foo", "lorem\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with_args(&["--today", "tomorrow"])?;
+    assert_ne!(horton.exit_code, Some(0));
+    assert!(horton.has_result("toolbox-error", "not a YYYY-MM-DD date", None));
+
+    Ok(())
+}
+
+#[test]
+fn ratchet_only_reports_growth() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    ratchet = true
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write("alpha.foo", "// TODO: one\n// TODO: two\n".as_bytes());
+    test_repo.write("beta.foo", "// TODO: one\n".as_bytes());
+    test_repo.git_commit_all("existing debt");
+
+    // Same count, different text: no findings
+    test_repo.write(
+        "alpha.foo",
+        "// TODO: one\nlorem\n// TODO: two\n".as_bytes(),
+    );
+    // One more TODO than upstream
+    test_repo.write("beta.foo", "// TODO: one\n// FIXME: two\n".as_bytes());
+    // New file with a TODO
+    test_repo.write("gamma.foo", "// TODO: new\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("todo"));
+    assert!(!horton.has_result("todo-ratchet", "", Some("alpha.foo")));
+    assert!(horton.has_result(
+        "todo-ratchet",
+        "todo count increased from 1 to 2",
+        Some("beta.foo")
+    ));
+    assert!(horton.has_result(
+        "todo-ratchet",
+        "todo count increased from 0 to 1",
+        Some("gamma.foo")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn ratchet_ignores_suppressed_todos() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    ratchet = true
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write("alpha.foo", "// TODO: one\n".as_bytes());
+    test_repo.git_commit_all("existing debt");
+
+    // A new TODO that is suppressed does not grow the count
+    test_repo.write(
+        "alpha.foo",
+        "// TODO: one\n// TODO: two toolbox-ignore(todo)\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result_with_rule_id("todo-ratchet"),
+        "{}",
+        horton
+    );
+    assert!(!horton.has_result_with_rule_id("toolbox-unused-suppression"));
+
+    // Without the suppression it does
+    test_repo.write("alpha.foo", "// TODO: one\n// TODO: two\n".as_bytes());
+    let horton = test_repo.run_horton()?;
+    assert!(horton.has_result(
+        "todo-ratchet",
+        "todo count increased from 1 to 2",
+        Some("alpha.foo")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn todo_only_in_comments() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    spans = ["comment"]
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write(
+        "alpha.py",
+        concat!(
+            "label = \"todo: shown in the UI\"\n",
+            "todo (items)\n",
+            "x = 1  # TODO: real\n",
+            "\"\"\"\n",
+            "TODO inside a docstring\n",
+            "\"\"\"\n",
+        )
+        .as_bytes(),
+    );
+    // Unknown languages are scanned in full
+    test_repo.write("alpha.foo", "label = \"todo: anywhere\"\n".as_bytes());
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.py:0:"), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.py:1:"), "{}", horton);
+    assert!(
+        horton.stdout.contains("alpha.py:2:9: Found 'TODO'"),
+        "{}",
+        horton
+    );
+    assert!(!horton.stdout.contains("alpha.py:4:"), "{}", horton);
+    assert!(
+        horton.stdout.contains("alpha.foo:0:9: Found 'todo'"),
+        "{}",
+        horton
+    );
+
+    Ok(())
+}
+
+#[test]
+fn todos_inventory() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write(
+        "src/alpha.rs",
+        "// TODO(alice, ABC-12): split this up\nfn main() {}\n".as_bytes(),
+    );
+    test_repo.write("beta.py", "x = 1  # FIXME: handle, \"quotes\"\n".as_bytes());
+    test_repo.git_commit_all("add files");
+    test_repo.write(
+        "src/alpha.rs",
+        "// TODO(alice, ABC-12): split this up\nfn main() {}\n// TODO(bob): new\n".as_bytes(),
+    );
+
+    let json = test_repo.run_toolbox_args(&["todos", "--format", "json"])?;
+    assert_eq!(json.exit_code, Some(0), "{}", json);
+    let items: Vec<serde_json::Value> = serde_json::from_str(&json.stdout)?;
+    assert_eq!(items.len(), 3, "{}", json.stdout);
+    assert_eq!(items[1]["path"], "src/alpha.rs");
+    assert_eq!(items[1]["line"], 1);
+    assert_eq!(items[1]["owner"], "alice");
+    assert_eq!(items[1]["ticket"], "ABC-12");
+    assert_eq!(items[1]["text"], "split this up");
+    assert!(items[1]["age_days"].is_i64());
+    // Uncommitted lines have no age
+    assert_eq!(items[2]["owner"], "bob");
+    assert!(items[2]["age_days"].is_null());
+
+    let csv = test_repo.run_toolbox_args(&["todos"])?;
+    assert!(
+        csv.stdout
+            .starts_with("path,line,token,owner,ticket,age_days,text\n"),
+        "{}",
+        csv.stdout
+    );
+    assert!(csv
+        .stdout
+        .contains("beta.py,1,FIXME,,,0,\"handle, \"\"quotes\"\"\"\n"));
+
+    let markdown =
+        test_repo.run_toolbox_args(&["todos", "--format", "markdown", "--group-by", "owner"])?;
+    assert!(
+        markdown.stdout.contains("## alice (1)"),
+        "{}",
+        markdown.stdout
+    );
+    assert!(markdown.stdout.contains("## bob (1)"));
+    assert!(markdown.stdout.contains("## (none) (1)"));
+
+    // Only the requested columns, in order; no age means no blame
+    let csv = test_repo.run_toolbox_args(&["todos", "--columns", "line,path,owner"])?;
+    assert_eq!(csv.exit_code, Some(0), "{}", csv);
+    assert!(
+        csv.stdout.starts_with("line,path,owner\n1,beta.py,\n"),
+        "{}",
+        csv.stdout
+    );
+    let json =
+        test_repo.run_toolbox_args(&["todos", "--format", "json", "--columns", "path,text"])?;
+    let items: Vec<serde_json::Value> = serde_json::from_str(&json.stdout)?;
+    assert_eq!(
+        items[0],
+        serde_json::json!({ "path": "beta.py", "text": "handle, \"quotes\"" })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ticket_index_set_bare_todo() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+
+    let config = r#"
+    [todo]
+    enabled = true
+    ticket_index = "tickets.json"
+"#;
+    test_repo.write("toolbox.toml", config.as_bytes());
+    test_repo.write("tickets.json", r#"["ABC-1"]"#.as_bytes());
+    test_repo.write(
+        "alpha.foo",
+        "// TODO fix me\n// TODO(ABC-1): open\n// TODO(ABC-9): closed\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+    let horton = test_repo.run_horton()?;
+
+    assert!(horton.has_result("todo", "Found 'TODO'", Some("alpha.foo")));
+    assert!(horton.has_result("todo-stale-ticket", "ABC-9", Some("alpha.foo")));
+    assert!(!horton.has_result("todo-stale-ticket", "ABC-1", Some("alpha.foo")));
+    assert_eq!(horton.results.matches("\"todo\"").count(), 1, "{}", horton);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML: &str = r#"
+[donotland]
+enabled = false
+
+[[tokens]]
+code = "hack"
+pattern = "\\bHACK\\b"
+severity = "error"
+message = "Remove '{match}' before landing"
+
+[[tokens]]
+code = "skip-review"
+pattern = "@skip-review"
+paths = ["src/**"]
+exclude = ["src/vendored/**"]
+"#;
+
+#[test]
+fn custom_token_matches() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write("alpha.foo", "lorem\n// HACK around a bug\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("hack", "Remove 'HACK' before landing", Some("alpha.foo")));
+
+    Ok(())
+}
+
+#[test]
+fn custom_token_honors_paths_and_exclude() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML);
+    test_repo.write("src/lib.foo", "@skip-review\n".as_bytes());
+    test_repo.write("src/vendored/lib.foo", "@skip-review\n".as_bytes());
+    test_repo.write("docs/readme.foo", "@skip-review\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("skip-review", "Found '@skip-review'", Some("src/lib.foo")));
+    assert!(!horton.has_result("skip-review", "", Some("src/vendored/lib.foo")));
+    assert!(!horton.has_result("skip-review", "", Some("docs/readme.foo")));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_token_pattern_is_a_rule_error() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[[tokens]]
+code = "broken"
+pattern = "(unclosed"
+"#,
+    );
+    test_repo.write("alpha.foo", "lorem\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_ne!(horton.exit_code, Some(0));
+    assert!(horton.has_result(
+        "toolbox-rule-error",
+        "invalid pattern for token 'broken'",
+        None
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn custom_token_spans() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[[tokens]]
+code = "xxx"
+pattern = "XXX"
+spans = ["code", "comment"]
+"#,
+    );
+    test_repo.write(
+        "alpha.ts",
+        "const s = 'XXX';\nconst t = XXX; /* XXX */\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton_with("HEAD", "text", false)?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.stdout.contains("alpha.ts:0:"), "{}", horton);
+    assert!(
+        horton.stdout.contains("alpha.ts:1:10: Found 'XXX'"),
+        "{}",
+        horton
+    );
+
+    Ok(())
+}
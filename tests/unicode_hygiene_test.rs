@@ -0,0 +1,116 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[unicodehygiene]
+enabled = true
+"#;
+
+#[test]
+fn reports_each_category() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write(
+        "src/lib.rs",
+        concat!(
+            "// check \u{202E}nimda\u{2066}\n",
+            "let s = \"a\u{200B}b\";\n",
+            "let\u{00A0}x = 1;\n",
+            "let y = 2 \u{2013} 1; // a dash \u{2014} in prose\n",
+            "let z \u{FF1D} 3;\n",
+            "fn p\u{0430}ssword() {}\n",
+            "let \u{043F}\u{0440}\u{0438}\u{0432}\u{0435}\u{0442} = 1;\n",
+        )
+        .as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("unicode-bidi", "U+202E", Some("src/lib.rs")));
+    assert!(horton.has_result("unicode-bidi", "U+2066", Some("src/lib.rs")));
+    assert!(horton.has_result("unicode-zero-width", "U+200B", Some("src/lib.rs")));
+    assert!(horton.has_result("unicode-nbsp", "U+00A0", Some("src/lib.rs")));
+    assert!(horton.has_fix_with_content("unicode-nbsp", " "));
+    assert!(horton.has_result("unicode-dash", "U+2013", Some("src/lib.rs")));
+    // Dashes in comments are prose
+    assert!(!horton.has_result("unicode-dash", "U+2014", Some("src/lib.rs")));
+    assert!(horton.has_result("unicode-fullwidth", "U+FF1D", Some("src/lib.rs")));
+    assert!(horton.has_fix_with_content("unicode-fullwidth", "="));
+    assert!(horton.has_result(
+        "unicode-confusable",
+        "did you mean 'password'?",
+        Some("src/lib.rs")
+    ));
+    assert!(horton.has_fix_with_content("unicode-confusable", "a"));
+    // Identifiers written entirely in another script are fine
+    assert!(!horton.has_result("unicode-confusable", "\u{0438}", Some("src/lib.rs")));
+
+    Ok(())
+}
+
+#[test]
+fn categories_are_configurable() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(
+        r#"
+[unicodehygiene]
+enabled = true
+categories = ["bidi"]
+"#,
+    );
+    test_repo.write(
+        "notes.txt",
+        "caf\u{00E9}\u{00A0}au lait \u{2066}x\u{2069}\n".as_bytes(),
+    );
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("unicode-bidi", "U+2066", Some("notes.txt")));
+    assert!(horton.has_result("unicode-bidi", "U+2069", Some("notes.txt")));
+    assert!(!horton.has_result_with_rule_id("unicode-nbsp"));
+
+    Ok(())
+}
+
+#[test]
+fn disabled_by_default() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("src/lib.rs", "// \u{202E}\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(!horton.has_result_with_rule_id("unicode-bidi"));
+
+    Ok(())
+}
+
+#[test]
+fn deleted_files_are_skipped() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("gone.txt", "gone\n".as_bytes());
+    test_repo.write("keep.txt", "keep\n".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add files");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.delete("gone.txt");
+    test_repo.write("keep.txt", "a\u{200B}b\n".as_bytes());
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result_with_rule_id("toolbox-rule-error"),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result("unicode-zero-width", "", Some("keep.txt")));
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+mod integration_testing;
+use integration_testing::TestRepo;
+
+const TOML_ON: &str = r#"
+[whitespace]
+enabled = true
+"#;
+
+#[test]
+fn trailing_whitespace_and_blank_lines() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("alpha.txt", "one  \ntwo\t\nthree\n\n\n".as_bytes());
+    test_repo.write("beta.txt", "no newline".as_bytes());
+    test_repo.write("clean.txt", "clean\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result("trailing-whitespace", "", Some("alpha.txt")));
+    assert!(horton.has_result(
+        "trailing-blank-lines",
+        "File ends with 2 blank line(s)",
+        Some("alpha.txt")
+    ));
+    assert!(horton.has_result(
+        "missing-final-newline",
+        "File does not end with a newline",
+        Some("beta.txt")
+    ));
+    assert!(horton.has_fix_with_content("missing-final-newline", "\n"));
+    assert!(!horton.has_result("trailing-whitespace", "", Some("clean.txt")));
+    assert!(!horton.has_result("missing-final-newline", "", Some("alpha.txt")));
+
+    Ok(())
+}
+
+#[test]
+fn mixed_line_endings() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write("win.txt", "a\r\nb\r\nc\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "mixed-line-endings",
+        "1 line(s) end with LF instead of CRLF",
+        Some("win.txt")
+    ));
+    assert!(horton.has_fix_with_content("mixed-line-endings", "\r"));
+
+    Ok(())
+}
+
+#[test]
+fn indentation_from_config_and_editorconfig() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.write(
+        ".editorconfig",
+        "root = true\n\n[*.go]\nindent_style = tab\n\n[*.py]\nindent_style = space\nindent_size = 4\n"
+            .as_bytes(),
+    );
+    test_repo.write(
+        "main.go",
+        "func main() {\n    x := 1\n\ty := 2\n}\n".as_bytes(),
+    );
+    test_repo.write("main.py", "def f():\n\treturn 1\n".as_bytes());
+    test_repo.write("notes.txt", "\tanything\n    goes\n".as_bytes());
+    test_repo.git_add_all()?;
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(horton.has_result(
+        "inconsistent-indentation",
+        "Indentation should use tabs",
+        Some("main.go")
+    ));
+    assert!(horton.has_fix_with_content("inconsistent-indentation", "\t"));
+    assert!(horton.has_result(
+        "inconsistent-indentation",
+        "Indentation should use spaces",
+        Some("main.py")
+    ));
+    assert!(horton.has_fix_with_content("inconsistent-indentation", "    "));
+    assert!(!horton.has_result("inconsistent-indentation", "", Some("notes.txt")));
+
+    // An explicit style applies to every file
+    test_repo.set_toolbox_toml("[whitespace]\nenabled = true\nindent_style = \"space\"\n");
+    let horton = test_repo.run_horton()?;
+    assert!(horton.has_result("inconsistent-indentation", "", Some("notes.txt")));
+
+    Ok(())
+}
+
+#[test]
+fn deleted_files_are_skipped() -> anyhow::Result<()> {
+    let test_repo = TestRepo::make()?;
+    test_repo.write("gone.txt", "gone\n".as_bytes());
+    test_repo.write("keep.txt", "keep\n".as_bytes());
+    test_repo.git_add_all()?;
+    test_repo.git_commit_all("add files");
+
+    test_repo.set_toolbox_toml(TOML_ON);
+    test_repo.delete("gone.txt");
+    test_repo.write("keep.txt", "keep  \n".as_bytes());
+
+    let horton = test_repo.run_horton()?;
+
+    assert_eq!(horton.exit_code, Some(0), "{}", horton);
+    assert!(
+        !horton.has_result_with_rule_id("toolbox-rule-error"),
+        "{}",
+        horton
+    );
+    assert!(horton.has_result("trailing-whitespace", "", Some("keep.txt")));
+
+    Ok(())
+}